@@ -0,0 +1,86 @@
+//! Exposes the build provenance recorded with the results
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    let root = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set");
+    let root = Path::new(&root);
+
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    if root.join(".git/HEAD").exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        println!("cargo:rerun-if-changed=.git/index");
+    }
+
+    if let Some(commit) = git_commit(root) {
+        println!("cargo:rustc-env=BENCH_GIT_COMMIT={}", commit);
+    }
+    if let Some(version) = locked_version(root, "solana-program") {
+        println!("cargo:rustc-env=BENCH_SDK_VERSION={}", version);
+    }
+    if let Some(version) = rustc_version() {
+        println!("cargo:rustc-env=BENCH_RUSTC_VERSION={}", version);
+    }
+
+    let env = |name: &str| std::env::var(name).unwrap_or_default();
+    println!("cargo:rustc-env=BENCH_TARGET={}", env("TARGET"));
+    println!("cargo:rustc-env=BENCH_PROFILE={}", env("PROFILE"));
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(name, _)| {
+            name.strip_prefix("CARGO_FEATURE_")
+                .map(|feature| feature.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=BENCH_FEATURES={}", features.join(","));
+}
+
+/// Version of the compiler building the benchmark
+fn rustc_version() -> Option<String> {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Hash of the checked out commit, marked dirty if there are uncommitted changes
+fn git_commit(root: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+    let commit = git(&["rev-parse", "--short=12", "HEAD"])?;
+    let dirty = matches!(
+        git(&["status", "--porcelain", "--untracked-files=no"]),
+        Some(status) if !status.is_empty()
+    );
+    Some(if dirty {
+        format!("{}-dirty", commit)
+    } else {
+        commit
+    })
+}
+
+/// Version of the package resolved in Cargo.lock
+fn locked_version(root: &Path, package: &str) -> Option<String> {
+    let lock = fs::read_to_string(root.join("Cargo.lock")).ok()?;
+    let name = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line == name {
+            return lines
+                .next()?
+                .strip_prefix("version = \"")?
+                .strip_suffix('"')
+                .map(str::to_owned);
+        }
+    }
+    None
+}
@@ -1,57 +1,80 @@
 //! alt-bn128-bench alb-bn128 implementation.
 //! Original implementation: solana/programs/bpf_loader/src/syscalls.rs
 
+use crate::alt_bn128_compression::*;
 use solana_rbpf::error::{EbpfError, UserDefinedError};
 use solana_rbpf::memory_region::{AccessType, MemoryMapping};
+use solana_rbpf::vm::Config;
 use solana_sdk::alt_bn128::prelude::*;
+use solana_sdk::bpf_loader_deprecated;
+use solana_sdk::bpf_loader_upgradeable;
 use solana_sdk::instruction::InstructionError;
 use solana_sdk::pubkey::{Pubkey, PubkeyError};
+use std::cell::RefCell;
 use std::fmt;
 use std::mem::{align_of, size_of};
+use std::rc::Rc;
 use std::slice::from_raw_parts_mut;
-use std::str::{FromStr, Utf8Error};
+use std::str::Utf8Error;
 
 /// Error definitions
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum SyscallError {
+    /// String argument is not valid UTF-8
     #[error("{0}: {1:?}")]
     InvalidString(Utf8Error, Vec<u8>),
+    /// Program aborted
     #[error("BPF program panicked")]
     Abort,
+    /// Program panicked in the given file, line and column
     #[error("BPF program Panicked in {0} at {1}:{2}")]
     Panic(String, u64, u64),
+    /// Invoke context is already borrowed
     #[error("cannot borrow invoke context")]
     InvokeContextBorrowFailed,
+    /// Signer seed is not valid UTF-8
     #[error("malformed signer seed: {0}: {1:?}")]
     MalformedSignerSeed(Utf8Error, Vec<u8>),
+    /// Program address can not be derived from the signer seeds
     #[error("Could not create program address with signer seeds: {0}")]
     BadSeeds(PubkeyError),
+    /// Program can not be invoked by inner instructions
     #[error("Program {0} not supported by inner instructions")]
     ProgramNotSupported(Pubkey),
+    /// Instruction error, such as an exceeded compute budget
     #[error("{0}")]
     InstructionError(InstructionError),
+    /// Pointer is not aligned for its type
     #[error("Unaligned pointer")]
     UnalignedPointer,
+    /// Too many signers of an inner instruction
     #[error("Too many signers")]
     TooManySigners,
+    /// Inner instruction data exceeds the limit
     #[error("Instruction passed to inner instruction is too large ({0} > {1})")]
     InstructionTooLarge(usize, usize),
+    /// Too many accounts of an inner instruction
     #[error("Too many accounts passed to inner instruction")]
     TooManyAccounts,
+    /// Syscall argument selecting the operation is not supported
+    #[error("Invalid Attribute")]
+    InvalidAttribute,
 }
 
 /// Errors returned by functions the BPF Loader registers with the VM
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum BpfError {
+    /// Program failed the verification
     VerifierError,
-    SyscallError,
+    /// Syscall failed with the given error
+    SyscallError(#[source] SyscallError),
 }
 
 impl fmt::Display for BpfError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             BpfError::VerifierError => write!(f, "VerifierError"),
-            BpfError::SyscallError => write!(f, "SyscallError"),
+            BpfError::SyscallError(error) => write!(f, "SyscallError: {}", error),
         }
     }
 }
@@ -59,8 +82,8 @@ impl fmt::Display for BpfError {
 impl UserDefinedError for BpfError {}
 
 impl From<SyscallError> for BpfError {
-    fn from(_error: SyscallError) -> Self {
-        BpfError::SyscallError
+    fn from(error: SyscallError) -> Self {
+        BpfError::SyscallError(error)
     }
 }
 
@@ -70,6 +93,18 @@ impl From<SyscallError> for EbpfError<BpfError> {
     }
 }
 
+/// Name of the alt_bn128 error variant, without its data
+pub fn alt_bn128_error_name(error: &AltBn128Error) -> &'static str {
+    match error {
+        AltBn128Error::InvalidInputData => "InvalidInputData",
+        AltBn128Error::GroupError => "GroupError",
+        AltBn128Error::SliceOutOfBounds => "SliceOutOfBounds",
+        AltBn128Error::UnexpectedError => "UnexpectedError",
+        AltBn128Error::TryIntoVecError(_) => "TryIntoVecError",
+        AltBn128Error::ProjectiveToG1Failed => "ProjectiveToG1Failed",
+    }
+}
+
 /// Error handling for SyscallObject::call methods
 macro_rules! question_mark {
     ( $value:expr, $result:ident ) => {{
@@ -84,6 +119,134 @@ macro_rules! question_mark {
     }};
 }
 
+/// Size of a single (G1, G2) element of the pairing input
+const ALT_BN128_PAIRING_ELEMENT_LEN: u64 = 192;
+
+/// Compute units charged by the alt_bn128 syscalls
+#[derive(Clone, Copy, Debug)]
+pub struct AltBn128Costs {
+    /// Compute units per Addition call
+    pub addition: u64,
+    /// Compute units per Multiplication call
+    pub multiplication: u64,
+    /// Compute units for the first pair of a Pairing call
+    pub pairing_one_pair_cost_first: u64,
+    /// Compute units for every other pair of a Pairing call
+    pub pairing_one_pair_cost_other: u64,
+    /// Compute units per G1 Compression call
+    pub g1_compress: u64,
+    /// Compute units per G1 Decompression call
+    pub g1_decompress: u64,
+    /// Compute units per G2 Compression call
+    pub g2_compress: u64,
+    /// Compute units per G2 Decompression call
+    pub g2_decompress: u64,
+}
+
+impl Default for AltBn128Costs {
+    fn default() -> Self {
+        Self {
+            addition: 334,
+            multiplication: 3_840,
+            pairing_one_pair_cost_first: 36_364,
+            pairing_one_pair_cost_other: 12_121,
+            g1_compress: 30,
+            g1_decompress: 398,
+            g2_compress: 86,
+            g2_decompress: 13_610,
+        }
+    }
+}
+
+impl AltBn128Costs {
+    /// Cost of a pairing check over the input of the given size
+    pub fn pairing(&self, input_size: u64) -> u64 {
+        let pairs = input_size / ALT_BN128_PAIRING_ELEMENT_LEN;
+        self.pairing_one_pair_cost_first.saturating_add(
+            self.pairing_one_pair_cost_other
+                .saturating_mul(pairs.saturating_sub(1)),
+        )
+    }
+
+    /// Cost of the given compression operation, `None` if the operation is not supported
+    pub fn compression(&self, op: u64) -> Option<u64> {
+        match op {
+            ALT_BN128_G1_COMPRESS => Some(self.g1_compress),
+            ALT_BN128_G1_DECOMPRESS => Some(self.g1_decompress),
+            ALT_BN128_G2_COMPRESS => Some(self.g2_compress),
+            ALT_BN128_G2_DECOMPRESS => Some(self.g2_decompress),
+            _ => None,
+        }
+    }
+}
+
+/// Local stand-in for the invoke context compute meter
+#[derive(Debug)]
+pub struct ComputeMeter {
+    remaining: u64,
+}
+
+impl ComputeMeter {
+    /// Creates the meter with the given budget
+    pub fn new(remaining: u64) -> Self {
+        Self { remaining }
+    }
+
+    /// Consumes compute units, fails when the budget is exceeded
+    pub fn consume(&mut self, amount: u64) -> Result<(), InstructionError> {
+        let exceeded = self.remaining < amount;
+        self.remaining = self.remaining.saturating_sub(amount);
+        if exceeded {
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        Ok(())
+    }
+
+    /// Compute units left in the budget
+    pub fn get_remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+/// Cluster configuration the syscalls run with, shared by all the syscall objects and the runner
+#[derive(Clone, Copy, Debug)]
+pub struct SyscallContext {
+    /// Loader of the calling program, the deprecated loader skips the alignment checks
+    pub loader_id: Pubkey,
+    /// Configuration of the VM the memory mappings are created with
+    pub config: Config,
+    /// `enforce_aligned_host_addrs` feature of the cluster, without it the VM addresses
+    /// are checked for alignment as well as the host addresses
+    pub enforce_aligned_host_addrs: bool,
+    /// Compute units charged by the metered syscalls
+    pub costs: AltBn128Costs,
+}
+
+impl Default for SyscallContext {
+    fn default() -> Self {
+        Self {
+            loader_id: bpf_loader_upgradeable::id(),
+            config: Config::default(),
+            enforce_aligned_host_addrs: true,
+            costs: AltBn128Costs::default(),
+        }
+    }
+}
+
+/// Charges the compute meter of a syscall, if any
+fn consume_compute_meter(
+    compute_meter: &Option<Rc<RefCell<ComputeMeter>>>,
+    amount: u64,
+) -> Result<(), EbpfError<BpfError>> {
+    if let Some(compute_meter) = compute_meter {
+        compute_meter
+            .borrow_mut()
+            .consume(amount)
+            .map_err(SyscallError::InstructionError)?;
+    }
+    Ok(())
+}
+
 fn translate(
     memory_mapping: &MemoryMapping,
     access_type: AccessType,
@@ -93,16 +256,63 @@ fn translate(
     memory_mapping.map::<BpfError>(access_type, vm_addr, len)
 }
 
+/// Checks the VM and host addresses of a slice of `T` as the loader of the cluster does: without
+/// the `enforce_aligned_host_addrs` feature the VM address must be aligned for `T`, with it the
+/// host address, and the deprecated loader checks neither. Byte slices are always aligned.
+pub fn check_alignment<T>(
+    vm_addr: u64,
+    host_addr: u64,
+    loader_id: &Pubkey,
+    enforce_aligned_host_addrs: bool,
+) -> Result<(), EbpfError<BpfError>> {
+    if unaligned_vm_addr::<T>(vm_addr, loader_id, enforce_aligned_host_addrs)
+        || unaligned_host_addr::<T>(host_addr, loader_id, enforce_aligned_host_addrs)
+    {
+        return Err(SyscallError::UnalignedPointer.into());
+    }
+    Ok(())
+}
+
+/// Whether the loader refuses the VM address of a slice of `T`, see `check_alignment`
+fn unaligned_vm_addr<T>(
+    vm_addr: u64,
+    loader_id: &Pubkey,
+    enforce_aligned_host_addrs: bool,
+) -> bool {
+    !enforce_aligned_host_addrs
+        && loader_id != &bpf_loader_deprecated::id()
+        && is_misaligned::<T>(vm_addr)
+}
+
+/// Whether the loader refuses the host address of a slice of `T`, see `check_alignment`
+fn unaligned_host_addr<T>(
+    host_addr: u64,
+    loader_id: &Pubkey,
+    enforce_aligned_host_addrs: bool,
+) -> bool {
+    enforce_aligned_host_addrs
+        && loader_id != &bpf_loader_deprecated::id()
+        && is_misaligned::<T>(host_addr)
+}
+
+/// Whether the address is not aligned for `T`
+fn is_misaligned<T>(addr: u64) -> bool {
+    (addr as *mut T).align_offset(align_of::<T>()) != 0
+}
+
+/// Translates the VM address of a slice to a host slice with the alignment checks of the loader
+/// of the cluster, see `check_alignment`. Where the cluster hands a misaligned host slice to the
+/// syscall, building it is undefined behaviour in Rust and aborts the debug builds, so it is
+/// refused here as well.
 fn translate_slice_inner<'a, T>(
     memory_mapping: &MemoryMapping,
     access_type: AccessType,
     vm_addr: u64,
     len: u64,
-    _loader_id: &Pubkey,
+    loader_id: &Pubkey,
     enforce_aligned_host_addrs: bool,
 ) -> Result<&'a mut [T], EbpfError<BpfError>> {
-    if !enforce_aligned_host_addrs && (vm_addr as u64 as *mut T).align_offset(align_of::<T>()) != 0
-    {
+    if unaligned_vm_addr::<T>(vm_addr, loader_id, enforce_aligned_host_addrs) {
         return Err(SyscallError::UnalignedPointer.into());
     }
     if len == 0 {
@@ -115,13 +325,16 @@ fn translate_slice_inner<'a, T>(
         len.saturating_mul(size_of::<T>() as u64),
     )?;
 
-    if enforce_aligned_host_addrs && (host_addr as *mut T).align_offset(align_of::<T>()) != 0 {
+    if unaligned_host_addr::<T>(host_addr, loader_id, enforce_aligned_host_addrs)
+        || is_misaligned::<T>(host_addr)
+    {
         return Err(SyscallError::UnalignedPointer.into());
     }
     Ok(unsafe { from_raw_parts_mut(host_addr as *mut T, len as usize) })
 }
 
-fn translate_slice_mut<'a, T>(
+/// Translates the VM address of a writable slice, see `translate_slice`
+pub fn translate_slice_mut<'a, T>(
     memory_mapping: &MemoryMapping,
     vm_addr: u64,
     len: u64,
@@ -138,7 +351,10 @@ fn translate_slice_mut<'a, T>(
     )
 }
 
-fn translate_slice<'a, T>(
+/// Translates the VM address of a slice of `len` elements to a host slice, fails with
+/// `SyscallError::UnalignedPointer` if the host address or, without `enforce_aligned_host_addrs`
+/// and outside of the deprecated loader, the VM address is not aligned for `T`
+pub fn translate_slice<'a, T>(
     memory_mapping: &MemoryMapping,
     vm_addr: u64,
     len: u64,
@@ -156,19 +372,33 @@ fn translate_slice<'a, T>(
     .map(|value| &*value)
 }
 
-// ALT-BN128 Addition
+/// alt_bn128 Addition syscall, adds two G1 points
 pub struct SyscallAltBn128Addition {
-    loader_id: Pubkey,
+    context: SyscallContext,
+    compute_meter: Option<Rc<RefCell<ComputeMeter>>>,
 }
 
 impl SyscallAltBn128Addition {
-    pub fn new() -> Self {
+    /// Creates the syscall running in the given context without compute metering
+    pub fn new(context: &SyscallContext) -> Self {
         Self {
-            loader_id: Pubkey::from_str("Cj9ydNGWLePKRztuE3m3zT1uvj2We517k55vq2e65jtP")
-                .expect("Invalid solana_sdk::pubkey::Pubkey from string"),
+            context: *context,
+            compute_meter: None,
         }
     }
 
+    /// Creates the syscall charging the costs of the context to the given compute meter
+    pub fn with_compute_meter(
+        context: &SyscallContext,
+        compute_meter: Rc<RefCell<ComputeMeter>>,
+    ) -> Self {
+        Self {
+            context: *context,
+            compute_meter: Some(compute_meter),
+        }
+    }
+
+    /// Adds the points of the input, writes the sum to `result_addr`
     #[allow(clippy::too_many_arguments)]
     pub fn call(
         &self,
@@ -180,13 +410,18 @@ impl SyscallAltBn128Addition {
         memory_mapping: &MemoryMapping,
         result: &mut Result<u64, EbpfError<BpfError>>,
     ) {
+        question_mark!(
+            consume_compute_meter(&self.compute_meter, self.context.costs.addition),
+            result
+        );
+
         let input = question_mark!(
             translate_slice::<u8>(
                 memory_mapping,
                 input_addr,
                 input_size,
-                &self.loader_id,
-                true
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
             ),
             result
         );
@@ -196,8 +431,8 @@ impl SyscallAltBn128Addition {
                 memory_mapping,
                 result_addr,
                 ALT_BN128_ADDITION_OUTPUT_LEN as u64,
-                &self.loader_id,
-                true,
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
             ),
             result
         );
@@ -220,19 +455,33 @@ impl SyscallAltBn128Addition {
     }
 }
 
-// ALT-BN128 Multiplication
+/// alt_bn128 Multiplication syscall, multiplies a G1 point by a scalar
 pub struct SyscallAltBn128Multiplication {
-    loader_id: Pubkey,
+    context: SyscallContext,
+    compute_meter: Option<Rc<RefCell<ComputeMeter>>>,
 }
 
 impl SyscallAltBn128Multiplication {
-    pub fn new() -> Self {
+    /// Creates the syscall running in the given context without compute metering
+    pub fn new(context: &SyscallContext) -> Self {
+        Self {
+            context: *context,
+            compute_meter: None,
+        }
+    }
+
+    /// Creates the syscall charging the costs of the context to the given compute meter
+    pub fn with_compute_meter(
+        context: &SyscallContext,
+        compute_meter: Rc<RefCell<ComputeMeter>>,
+    ) -> Self {
         Self {
-            loader_id: Pubkey::from_str("Cj9ydNGWLePKRztuE3m3zT1uvj2We517k55vq2e65jtP")
-                .expect("Invalid solana_sdk::pubkey::Pubkey from string"),
+            context: *context,
+            compute_meter: Some(compute_meter),
         }
     }
 
+    /// Multiplies the point of the input by the scalar, writes the product to `result_addr`
     #[allow(clippy::too_many_arguments)]
     pub fn call(
         &self,
@@ -244,13 +493,18 @@ impl SyscallAltBn128Multiplication {
         memory_mapping: &MemoryMapping,
         result: &mut Result<u64, EbpfError<BpfError>>,
     ) {
+        question_mark!(
+            consume_compute_meter(&self.compute_meter, self.context.costs.multiplication),
+            result
+        );
+
         let input = question_mark!(
             translate_slice::<u8>(
                 memory_mapping,
                 input_addr,
                 input_size,
-                &self.loader_id,
-                true,
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
             ),
             result
         );
@@ -259,8 +513,8 @@ impl SyscallAltBn128Multiplication {
                 memory_mapping,
                 result_addr,
                 ALT_BN128_MULTIPLICATION_OUTPUT_LEN as u64,
-                &self.loader_id,
-                true,
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
             ),
             result
         );
@@ -283,19 +537,33 @@ impl SyscallAltBn128Multiplication {
     }
 }
 
-// ALT-BN128 Pairing
+/// alt_bn128 Pairing syscall, checks the product of the pairings of (G1, G2) pairs
 pub struct SyscallAltBn128Pairing {
-    loader_id: Pubkey,
+    context: SyscallContext,
+    compute_meter: Option<Rc<RefCell<ComputeMeter>>>,
 }
 
 impl SyscallAltBn128Pairing {
-    pub fn new() -> Self {
+    /// Creates the syscall running in the given context without compute metering
+    pub fn new(context: &SyscallContext) -> Self {
         Self {
-            loader_id: Pubkey::from_str("Cj9ydNGWLePKRztuE3m3zT1uvj2We517k55vq2e65jtP")
-                .expect("Invalid solana_sdk::pubkey::Pubkey from string"),
+            context: *context,
+            compute_meter: None,
         }
     }
 
+    /// Creates the syscall charging the costs of the context to the given compute meter
+    pub fn with_compute_meter(
+        context: &SyscallContext,
+        compute_meter: Rc<RefCell<ComputeMeter>>,
+    ) -> Self {
+        Self {
+            context: *context,
+            compute_meter: Some(compute_meter),
+        }
+    }
+
+    /// Checks that the product of the pairings of the input pairs is one, writes the 32-byte result to `result_addr`
     #[allow(clippy::too_many_arguments)]
     pub fn call(
         &self,
@@ -307,13 +575,18 @@ impl SyscallAltBn128Pairing {
         memory_mapping: &MemoryMapping,
         result: &mut Result<u64, EbpfError<BpfError>>,
     ) {
+        question_mark!(
+            consume_compute_meter(&self.compute_meter, self.context.costs.pairing(input_size)),
+            result
+        );
+
         let input = question_mark!(
             translate_slice::<u8>(
                 memory_mapping,
                 input_addr,
                 input_size,
-                &self.loader_id,
-                true
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
             ),
             result
         );
@@ -322,8 +595,8 @@ impl SyscallAltBn128Pairing {
                 memory_mapping,
                 result_addr,
                 ALT_BN128_PAIRING_OUTPUT_LEN as u64,
-                &self.loader_id,
-                true,
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
             ),
             result
         );
@@ -345,3 +618,192 @@ impl SyscallAltBn128Pairing {
         *result = Ok(0);
     }
 }
+
+/// alt_bn128 Compression syscall, compresses or decompresses a G1 or G2 point
+pub struct SyscallAltBn128Compression {
+    context: SyscallContext,
+    compute_meter: Option<Rc<RefCell<ComputeMeter>>>,
+}
+
+impl SyscallAltBn128Compression {
+    /// Creates the syscall running in the given context without compute metering
+    pub fn new(context: &SyscallContext) -> Self {
+        Self {
+            context: *context,
+            compute_meter: None,
+        }
+    }
+
+    /// Creates the syscall charging the costs of the context to the given compute meter
+    pub fn with_compute_meter(
+        context: &SyscallContext,
+        compute_meter: Rc<RefCell<ComputeMeter>>,
+    ) -> Self {
+        Self {
+            context: *context,
+            compute_meter: Some(compute_meter),
+        }
+    }
+
+    /// Runs the compression operation `op` on the point of the input, writes the compressed
+    /// or decompressed point to `result_addr`
+    #[allow(clippy::too_many_arguments)]
+    pub fn call(
+        &self,
+        op: u64,
+        input_addr: u64,
+        input_size: u64,
+        result_addr: u64,
+        _arg5: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        let (output_len, compression): (usize, AltBn128Compression) = match op {
+            ALT_BN128_G1_COMPRESS => (ALT_BN128_G1_COMPRESSED_LEN, alt_bn128_g1_compress),
+            ALT_BN128_G1_DECOMPRESS => (ALT_BN128_G1_LEN, alt_bn128_g1_decompress),
+            ALT_BN128_G2_COMPRESS => (ALT_BN128_G2_COMPRESSED_LEN, alt_bn128_g2_compress),
+            ALT_BN128_G2_DECOMPRESS => (ALT_BN128_G2_LEN, alt_bn128_g2_decompress),
+            _ => {
+                *result = Err(SyscallError::InvalidAttribute.into());
+                return;
+            }
+        };
+        let cost = question_mark!(
+            self.context
+                .costs
+                .compression(op)
+                .ok_or(SyscallError::InvalidAttribute),
+            result
+        );
+        question_mark!(consume_compute_meter(&self.compute_meter, cost), result);
+
+        let input = question_mark!(
+            translate_slice::<u8>(
+                memory_mapping,
+                input_addr,
+                input_size,
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
+            ),
+            result
+        );
+        let call_result = question_mark!(
+            translate_slice_mut::<u8>(
+                memory_mapping,
+                result_addr,
+                output_len as u64,
+                &self.context.loader_id,
+                self.context.enforce_aligned_host_addrs,
+            ),
+            result
+        );
+
+        let result_point = match compression(input) {
+            Ok(result_point) => result_point,
+            Err(e) => {
+                *result = Ok(e.into());
+                return;
+            }
+        };
+
+        if result_point.len() != output_len {
+            *result = Ok(AltBn128Error::SliceOutOfBounds.into());
+            return;
+        }
+
+        call_result.copy_from_slice(&result_point);
+        *result = Ok(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_rbpf::memory_region::MemoryRegion;
+
+    /// VM address of the checked slices, aligned for u64
+    const VM_ADDR: u64 = 0x400000000;
+
+    /// Host address of the checked slices, aligned for u64
+    const HOST_ADDR: u64 = 0x10000;
+
+    /// Checks a u64 slice at the given VM and host address offsets
+    fn check_words(
+        vm_offset: u64,
+        host_offset: u64,
+        loader_id: &Pubkey,
+        enforce_aligned_host_addrs: bool,
+    ) -> Result<(), EbpfError<BpfError>> {
+        check_alignment::<u64>(
+            VM_ADDR + vm_offset,
+            HOST_ADDR + host_offset,
+            loader_id,
+            enforce_aligned_host_addrs,
+        )
+    }
+
+    fn assert_unaligned(result: Result<(), EbpfError<BpfError>>) {
+        assert!(
+            matches!(
+                result,
+                Err(EbpfError::UserError(BpfError::SyscallError(
+                    SyscallError::UnalignedPointer
+                )))
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn misaligned_vm_address_is_refused_without_enforce_aligned_host_addrs() {
+        let upgradeable = bpf_loader_upgradeable::id();
+        assert_unaligned(check_words(1, 0, &upgradeable, false));
+        assert!(check_words(0, 1, &upgradeable, false).is_ok());
+    }
+
+    #[test]
+    fn misaligned_host_address_is_refused_with_enforce_aligned_host_addrs() {
+        let upgradeable = bpf_loader_upgradeable::id();
+        assert!(check_words(1, 0, &upgradeable, true).is_ok());
+        assert_unaligned(check_words(0, 1, &upgradeable, true));
+    }
+
+    #[test]
+    fn deprecated_loader_checks_no_alignment() {
+        let deprecated = bpf_loader_deprecated::id();
+        for &enforce_aligned_host_addrs in [false, true].iter() {
+            assert!(check_words(1, 0, &deprecated, enforce_aligned_host_addrs).is_ok());
+            assert!(check_words(0, 1, &deprecated, enforce_aligned_host_addrs).is_ok());
+        }
+    }
+
+    #[test]
+    fn misaligned_host_slice_is_not_built() {
+        let buffer = [0u64; 3];
+        let bytes = unsafe {
+            std::slice::from_raw_parts(buffer.as_ptr() as *const u8, size_of::<[u64; 3]>())
+        };
+        let config = Config::default();
+        let memory_mapping = MemoryMapping::new::<BpfError>(
+            vec![MemoryRegion::new_from_slice(
+                &bytes[1..17],
+                VM_ADDR,
+                0,
+                false,
+            )],
+            &config,
+        )
+        .unwrap();
+        let deprecated = bpf_loader_deprecated::id();
+        assert_unaligned(
+            translate_slice::<u64>(&memory_mapping, VM_ADDR, 2, &deprecated, false).map(|_| ()),
+        );
+        assert_eq!(
+            translate_slice::<u8>(&memory_mapping, VM_ADDR, 16, &deprecated, false)
+                .unwrap()
+                .len(),
+            16
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! alt-bn128-bench alt_bn128 point compression.
+//! Stand-in for `alt_bn128::compression` of the later Solana SDKs, which the SDK here lacks,
+//! on the same ark-bn254 backend and with the same encoding: the big-endian coordinates of
+//! EIP-196 and EIP-197, compressed to the x coordinate with the flags of ark-serialize in the
+//! top bits of its first byte. All zeros encode the point at infinity.
+
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize, SWFlags,
+};
+use solana_sdk::alt_bn128::prelude::AltBn128Error;
+
+/// Compression syscall operation compressing a G1 point
+pub const ALT_BN128_G1_COMPRESS: u64 = 0;
+/// Compression syscall operation decompressing a G1 point
+pub const ALT_BN128_G1_DECOMPRESS: u64 = 1;
+/// Compression syscall operation compressing a G2 point
+pub const ALT_BN128_G2_COMPRESS: u64 = 2;
+/// Compression syscall operation decompressing a G2 point
+pub const ALT_BN128_G2_DECOMPRESS: u64 = 3;
+
+/// Compression or decompression of a point
+pub type AltBn128Compression = fn(&[u8]) -> Result<Vec<u8>, AltBn128Error>;
+
+/// Size of an uncompressed G1 point in bytes
+pub const ALT_BN128_G1_LEN: usize = 64;
+/// Size of a compressed G1 point in bytes
+pub const ALT_BN128_G1_COMPRESSED_LEN: usize = 32;
+/// Size of an uncompressed G2 point in bytes
+pub const ALT_BN128_G2_LEN: usize = 128;
+/// Size of a compressed G2 point in bytes
+pub const ALT_BN128_G2_COMPRESSED_LEN: usize = 64;
+
+/// Compresses a G1 point to its x coordinate and the sign of y. The point is not validated.
+pub fn alt_bn128_g1_compress(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    if input.len() != ALT_BN128_G1_LEN {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+    if is_zero(input) {
+        return Ok(vec![0u8; ALT_BN128_G1_COMPRESSED_LEN]);
+    }
+    let point = G1Affine::deserialize_unchecked(&*reverse_chunks(input, 32))
+        .map_err(|_| AltBn128Error::InvalidInputData)?;
+    let mut output = Vec::with_capacity(ALT_BN128_G1_COMPRESSED_LEN);
+    point
+        .serialize(&mut output)
+        .map_err(|_| AltBn128Error::UnexpectedError)?;
+    Ok(reverse_chunks(&output, 32))
+}
+
+/// Decompresses a G1 point. The x coordinate is checked to be on the curve, the cofactor
+/// of G1 is one.
+pub fn alt_bn128_g1_decompress(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    if input.len() != ALT_BN128_G1_COMPRESSED_LEN {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+    if is_zero(input) {
+        return Ok(vec![0u8; ALT_BN128_G1_LEN]);
+    }
+    let (x, flags): (Fq, SWFlags) = Fq::deserialize_with_flags(&*reverse_chunks(input, 32))
+        .map_err(|_| AltBn128Error::InvalidInputData)?;
+    let point = match flags.is_positive() {
+        None => return Ok(vec![0u8; ALT_BN128_G1_LEN]),
+        Some(greatest) => {
+            G1Affine::get_point_from_x(x, greatest).ok_or(AltBn128Error::GroupError)?
+        }
+    };
+    let mut output = Vec::with_capacity(ALT_BN128_G1_LEN);
+    point
+        .x
+        .serialize(&mut output)
+        .and_then(|_| point.y.serialize(&mut output))
+        .map_err(|_| AltBn128Error::UnexpectedError)?;
+    Ok(reverse_chunks(&output, 32))
+}
+
+/// Compresses a G2 point to its x coordinate and the sign of y. The point is not validated.
+pub fn alt_bn128_g2_compress(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    if input.len() != ALT_BN128_G2_LEN {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+    if is_zero(input) {
+        return Ok(vec![0u8; ALT_BN128_G2_COMPRESSED_LEN]);
+    }
+    let point = G2Affine::deserialize_unchecked(&*reverse_chunks(input, 64))
+        .map_err(|_| AltBn128Error::InvalidInputData)?;
+    let mut output = Vec::with_capacity(ALT_BN128_G2_COMPRESSED_LEN);
+    point
+        .serialize(&mut output)
+        .map_err(|_| AltBn128Error::UnexpectedError)?;
+    Ok(reverse_chunks(&output, 64))
+}
+
+/// Decompresses a G2 point. The x coordinate is checked to be on the curve and the point
+/// to be in the prime order subgroup.
+pub fn alt_bn128_g2_decompress(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    if input.len() != ALT_BN128_G2_COMPRESSED_LEN {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+    if is_zero(input) {
+        return Ok(vec![0u8; ALT_BN128_G2_LEN]);
+    }
+    let (x, flags): (Fq2, SWFlags) = Fq2::deserialize_with_flags(&*reverse_chunks(input, 64))
+        .map_err(|_| AltBn128Error::InvalidInputData)?;
+    let point = match flags.is_positive() {
+        None => return Ok(vec![0u8; ALT_BN128_G2_LEN]),
+        Some(greatest) => {
+            G2Affine::get_point_from_x(x, greatest).ok_or(AltBn128Error::GroupError)?
+        }
+    };
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(AltBn128Error::GroupError);
+    }
+    let mut output = Vec::with_capacity(ALT_BN128_G2_LEN);
+    point
+        .x
+        .serialize(&mut output)
+        .and_then(|_| point.y.serialize(&mut output))
+        .map_err(|_| AltBn128Error::UnexpectedError)?;
+    Ok(reverse_chunks(&output, 64))
+}
+
+/// Whether the input encodes the point at infinity
+fn is_zero(input: &[u8]) -> bool {
+    input.iter().all(|byte| *byte == 0)
+}
+
+/// Reverses the byte order of every chunk of the given size, converting the big-endian
+/// coordinates to the little-endian ones of ark-serialize and back
+fn reverse_chunks(input: &[u8], size: usize) -> Vec<u8> {
+    input
+        .chunks(size)
+        .flat_map(|chunk| chunk.iter().rev())
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::Zero;
+
+    #[test]
+    fn point_at_infinity_survives_the_round_trip() {
+        for (len, compress, decompress) in [
+            (
+                ALT_BN128_G1_LEN,
+                alt_bn128_g1_compress as AltBn128Compression,
+                alt_bn128_g1_decompress as AltBn128Compression,
+            ),
+            (
+                ALT_BN128_G2_LEN,
+                alt_bn128_g2_compress,
+                alt_bn128_g2_decompress,
+            ),
+        ] {
+            let infinity = vec![0u8; len];
+            let compressed = compress(&infinity).unwrap();
+            assert_eq!(decompress(&compressed).unwrap(), infinity);
+        }
+    }
+
+    #[test]
+    fn infinity_flag_decompresses_to_zeros() {
+        let mut g1 = Vec::new();
+        G1Affine::zero().serialize(&mut g1).unwrap();
+        assert_eq!(
+            alt_bn128_g1_decompress(&reverse_chunks(&g1, 32)).unwrap(),
+            vec![0u8; ALT_BN128_G1_LEN]
+        );
+
+        let mut g2 = Vec::new();
+        G2Affine::zero().serialize(&mut g2).unwrap();
+        assert_eq!(
+            alt_bn128_g2_decompress(&reverse_chunks(&g2, 64)).unwrap(),
+            vec![0u8; ALT_BN128_G2_LEN]
+        );
+    }
+}
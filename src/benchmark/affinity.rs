@@ -0,0 +1,120 @@
+use std::fmt;
+
+/// Scheduling priority of the benchmark threads
+#[derive(Clone, Copy, Debug)]
+pub enum Priority {
+    /// Nice value of the normal scheduler, negative values need privileges
+    Nice(i32),
+    /// Priority of the FIFO real-time scheduler, needs privileges
+    RealTime(i32),
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Priority::Nice(nice) => write!(f, "nice {}", nice),
+            Priority::RealTime(priority) => write!(f, "real-time {}", priority),
+        }
+    }
+}
+
+/// CPUs sharing a physical core with the given CPU, the CPU itself included
+pub fn smt_siblings(cpu: usize) -> Vec<usize> {
+    let path = format!(
+        "/sys/devices/system/cpu/cpu{}/topology/thread_siblings_list",
+        cpu
+    );
+    let list = std::fs::read_to_string(path).unwrap_or_default();
+    let mut siblings: Vec<usize> = list
+        .trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((first, last)) => Some(first.parse().ok()?..=last.parse().ok()?),
+            None => range.parse().ok().map(|cpu| cpu..=cpu),
+        })
+        .flatten()
+        .collect();
+    if siblings.is_empty() {
+        siblings.push(cpu);
+    }
+    siblings
+}
+
+/// Pairs of the given CPUs sharing a physical core
+pub fn busy_siblings(cpus: &[usize]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, cpu) in cpus.iter().enumerate() {
+        for sibling in smt_siblings(*cpu) {
+            if sibling != *cpu && cpus[i + 1..].contains(&sibling) {
+                pairs.push((*cpu, sibling));
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(target_os = "linux")]
+pub use self::linux::{pin, set_priority};
+
+#[cfg(not(target_os = "linux"))]
+pub use self::unsupported::{pin, set_priority};
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::Priority;
+    use std::io;
+
+    /// Pins the calling thread to the given CPU
+    pub fn pin(cpu: usize) -> Result<(), String> {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return Err(format!("CPU {} is out of range", cpu));
+        }
+        let result = unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_SET(cpu, &mut set);
+            libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+        };
+        if result != 0 {
+            return Err(format!(
+                "Pinning to CPU {} failed: {}",
+                cpu,
+                io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sets the priority of the calling thread, inherited by the threads it spawns
+    pub fn set_priority(priority: Priority) -> Result<(), String> {
+        let result = match priority {
+            Priority::Nice(nice) => unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) },
+            Priority::RealTime(priority) => unsafe {
+                let param = libc::sched_param {
+                    sched_priority: priority,
+                };
+                libc::sched_setscheduler(0, libc::SCHED_FIFO, &param)
+            },
+        };
+        if result != 0 {
+            return Err(format!(
+                "Setting {} priority failed: {}",
+                priority,
+                io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod unsupported {
+    use super::Priority;
+
+    pub fn pin(_cpu: usize) -> Result<(), String> {
+        Err("CPU pinning is supported on Linux only".to_owned())
+    }
+
+    pub fn set_priority(_priority: Priority) -> Result<(), String> {
+        Err("Priority control is supported on Linux only".to_owned())
+    }
+}
@@ -0,0 +1,110 @@
+use super::INPUT_VM_ADDR;
+use crate::alt_bn128::{check_alignment, translate_slice, BpfError, SyscallContext};
+use crate::error::Error;
+use solana_rbpf::error::EbpfError;
+use solana_rbpf::memory_region::{MemoryMapping, MemoryRegion};
+use std::fmt;
+use std::mem::align_of;
+use tracing::info;
+
+/// Misaligned VM and host address offsets checked by the alignment report
+const OFFSETS: [(u64, usize); 4] = [(0, 0), (1, 0), (0, 1), (1, 1)];
+
+/// Size of the slices translated by the alignment report in bytes
+const SLICE_SIZE: usize = 16;
+
+/// Addresses the syscall inputs are mapped at
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Alignment {
+    /// Offset of the input VM address from an aligned address in bytes
+    pub vm_offset: u64,
+    /// Offset of the input host address from an aligned address in bytes
+    pub host_offset: usize,
+}
+
+impl Alignment {
+    /// VM address the input region is mapped at
+    pub fn input_addr(&self) -> u64 {
+        INPUT_VM_ADDR + self.vm_offset
+    }
+
+    /// Whether the inputs are mapped at misaligned addresses
+    pub fn is_misaligned(&self) -> bool {
+        self.vm_offset != 0 || self.host_offset != 0
+    }
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "VM address +{}, host address +{}",
+            self.vm_offset, self.host_offset
+        )
+    }
+}
+
+/// Translates a byte slice and a u64 slice at misaligned VM and host addresses with and without
+/// `enforce_aligned_host_addrs` for the loader of the context and logs which of them fail
+/// with an unaligned pointer, next to the outcome of the u64 slice on a cluster
+pub fn alignment_report(context: &SyscallContext) -> Result<(), Error> {
+    info!(
+        "> Start alignment checks of the syscall memory translation for loader {}...",
+        context.loader_id
+    );
+    let buffer = vec![0u8; SLICE_SIZE + 2 * align_of::<u64>()];
+    let aligned = buffer.as_ptr().align_offset(align_of::<u64>());
+
+    for enforce_aligned_host_addrs in [false, true].iter() {
+        for (vm_offset, host_offset) in OFFSETS.iter() {
+            let host = &buffer[aligned + host_offset..][..SLICE_SIZE];
+            let vm_addr = INPUT_VM_ADDR + vm_offset;
+            let memory_mapping = MemoryMapping::new::<BpfError>(
+                vec![MemoryRegion::new_from_slice(host, vm_addr, 0, false)],
+                &context.config,
+            )
+            .map_err(Error::Mapping)?;
+
+            let bytes = translate_slice::<u8>(
+                &memory_mapping,
+                vm_addr,
+                SLICE_SIZE as u64,
+                &context.loader_id,
+                *enforce_aligned_host_addrs,
+            )
+            .map(|_| ());
+            let words = translate_slice::<u64>(
+                &memory_mapping,
+                vm_addr,
+                (SLICE_SIZE / align_of::<u64>()) as u64,
+                &context.loader_id,
+                *enforce_aligned_host_addrs,
+            )
+            .map(|_| ());
+            let cluster = check_alignment::<u64>(
+                vm_addr,
+                host.as_ptr() as u64,
+                &context.loader_id,
+                *enforce_aligned_host_addrs,
+            );
+            info!(
+                "enforce_aligned_host_addrs {}, VM address +{}, host address +{}: u8 slice {}, u64 slice {} ({} on a cluster)",
+                enforce_aligned_host_addrs,
+                vm_offset,
+                host_offset,
+                outcome(bytes),
+                outcome(words),
+                outcome(cluster)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of a translation for the log
+fn outcome(result: Result<(), EbpfError<BpfError>>) -> String {
+    match result {
+        Ok(()) => "ok".to_owned(),
+        Err(e) => e.to_string(),
+    }
+}
@@ -1,62 +1,139 @@
-use super::PRECISION;
-use crate::alt_bn128::{BpfError, SyscallAltBn128Addition};
-use crate::significant;
-use cpu_time::ProcessTime;
-use solana_rbpf::vm::Config;
-use tracing::{error, info};
-
-const BENCHMARK_NAME: &str = "alt_bn128 Addition";
-
-/// Runs the alt bn128 Addition benchmark
-pub fn alt_bn128_bench_addition(inputs: &[Vec<u8>], k: f64) {
-    info!("> Start {} benchmark...", BENCHMARK_NAME);
-    let caller = SyscallAltBn128Addition::new();
-    let config = Config::default();
-
-    let now = ProcessTime::try_now().expect("Getting process time failed");
-    for input in inputs {
-        alt_bn128_run_addition(&caller, &config, input.as_ref());
-    }
-    let d = now.try_elapsed().expect("Getting process time failed");
+use super::harness::{Harness, Measurement};
+use super::mapping::PoolMapping;
+use super::pool::InputPool;
+use super::{measure_syscall, Alignment, OUTPUT_VM_ADDR};
+use crate::alt_bn128::{BpfError, ComputeMeter, SyscallAltBn128Addition, SyscallContext};
+use crate::error::Error;
+use solana_rbpf::error::EbpfError;
+use solana_rbpf::memory_region::MemoryMapping;
+use solana_sdk::alt_bn128::prelude::ALT_BN128_ADDITION_OUTPUT_LEN;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-    let nanos = d.as_nanos() as f64;
-    let total = nanos / 1E9;
-    let n = inputs.len() as f64;
-    let average = total / n;
+pub const BENCHMARK_NAME: &str = "alt_bn128 Addition";
 
-    info!("Finish {}", BENCHMARK_NAME);
-    info!(
-        "{} ({} executions) elapsed {} s.",
-        BENCHMARK_NAME,
-        n,
-        significant::precision(total, PRECISION)
-    );
-    info!(
-        "{} average: {} s. = {} K",
+/// Runs the alt bn128 Addition benchmark and returns the measurement
+pub fn alt_bn128_bench_addition(
+    pool: &InputPool,
+    harness: &Harness,
+    k: Option<f64>,
+    budget: Option<u64>,
+    context: &SyscallContext,
+    alignment: &Alignment,
+) -> Result<Measurement, Error> {
+    let compute_meter = budget.map(|units| Rc::new(RefCell::new(ComputeMeter::new(units))));
+    let caller = match &compute_meter {
+        Some(compute_meter) => {
+            SyscallAltBn128Addition::with_compute_meter(context, compute_meter.clone())
+        }
+        None => SyscallAltBn128Addition::new(context),
+    };
+    let mapping = PoolMapping::new(pool, context, alignment, ALT_BN128_ADDITION_OUTPUT_LEN)?;
+    let input_size = pool.input_size() as u64;
+
+    measure_syscall(
         BENCHMARK_NAME,
-        significant::precision(average, PRECISION),
-        significant::precision(average / k, PRECISION)
-    );
+        harness,
+        k,
+        |i| pool.index(i),
+        |i| {
+            alt_bn128_run_addition(
+                &caller,
+                mapping.memory_mapping(),
+                mapping.input_addr(pool.index(i)),
+                input_size,
+            )
+        },
+    )
 }
 
-/// Executes single alt_bn128 Addition call
+/// Executes single alt_bn128 Addition call on the input of the given size mapped at the given VM
+/// address, the output is written to `OUTPUT_VM_ADDR`
 #[inline]
-fn alt_bn128_run_addition(syscall: &SyscallAltBn128Addition, config: &Config, input: &[u8]) {
-    use solana_rbpf::error::EbpfError;
-    use solana_rbpf::memory_region::{MemoryMapping, MemoryRegion};
+pub fn alt_bn128_run_addition(
+    syscall: &SyscallAltBn128Addition,
+    memory_mapping: &MemoryMapping,
+    input_addr: u64,
+    input_size: u64,
+) -> Result<(), Error> {
+    let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+    syscall.call(
+        input_addr,
+        input_size,
+        OUTPUT_VM_ADDR,
+        0,
+        0,
+        memory_mapping,
+        &mut result,
+    );
 
-    let memory_mapping = MemoryMapping::new::<BpfError>(
-        vec![MemoryRegion::new_from_slice(input, 0, 0, true)],
-        config,
-    )
-    .unwrap();
+    match result.map_err(Error::Syscall)? {
+        0 => Ok(()),
+        code => Err(Error::AltBn128(code.into())),
+    }
+}
 
-    let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
-    syscall.call(0, input.len() as u64, 0, 0, 0, &memory_mapping, &mut result);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alt_bn128::alt_bn128_error_name;
+    use crate::benchmark::{budget_exceeded, generate_buffers_add, PoolLimits};
+
+    const LIMITS: PoolLimits = PoolLimits {
+        inputs: 1,
+        bytes: 1 << 16,
+    };
+
+    #[test]
+    fn failure_of_the_call_draining_the_budget_is_not_a_budget_stop() {
+        let context = SyscallContext::default();
+        let pool = generate_buffers_add(&LIMITS, 1).unwrap();
+        let mapping = PoolMapping::new(
+            &pool,
+            &context,
+            &Alignment::default(),
+            ALT_BN128_ADDITION_OUTPUT_LEN,
+        )
+        .unwrap();
+        let compute_meter = Rc::new(RefCell::new(ComputeMeter::new(context.costs.addition)));
+        let syscall = SyscallAltBn128Addition::with_compute_meter(&context, compute_meter.clone());
+        let unmapped = mapping.input_addr(0) + pool.input_size() as u64;
+        let error = alt_bn128_run_addition(
+            &syscall,
+            mapping.memory_mapping(),
+            unmapped,
+            pool.input_size() as u64,
+        )
+        .unwrap_err();
+        assert_eq!(compute_meter.borrow().get_remaining(), 0);
+        assert!(!budget_exceeded(&error), "{}", error);
+    }
 
-    if let Err(err) = result {
-        error!("{:?}", err);
-        panic!("{:?}", err);
+    #[test]
+    fn invalid_point_returns_the_named_alt_bn128_error() {
+        let context = SyscallContext::default();
+        let pool = InputPool::generate(&LIMITS, 1, 128, || vec![1; 128]);
+        let mapping = PoolMapping::new(
+            &pool,
+            &context,
+            &Alignment::default(),
+            ALT_BN128_ADDITION_OUTPUT_LEN,
+        )
+        .unwrap();
+        let error = alt_bn128_run_addition(
+            &SyscallAltBn128Addition::new(&context),
+            mapping.memory_mapping(),
+            mapping.input_addr(0),
+            pool.input_size() as u64,
+        )
+        .unwrap_err();
+        match &error {
+            Error::AltBn128(e) => assert!(
+                error.to_string().contains(alt_bn128_error_name(e)),
+                "{}",
+                error
+            ),
+            _ => panic!("Not an alt_bn128 error: {}", error),
+        }
     }
-    assert_eq!(result.unwrap(), 0);
 }
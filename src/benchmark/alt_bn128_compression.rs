@@ -0,0 +1,206 @@
+use super::harness::{Harness, Measurement};
+use super::mapping::PoolMapping;
+use super::pool::{InputPool, PoolLimits};
+use super::{measure_syscall, parse_hex, Alignment, OUTPUT_VM_ADDR, PAIRING_INPUT};
+use crate::alt_bn128::{BpfError, ComputeMeter, SyscallAltBn128Compression, SyscallContext};
+use crate::alt_bn128_compression::*;
+use crate::error::Error;
+use solana_rbpf::error::EbpfError;
+use solana_rbpf::memory_region::MemoryMapping;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub const G1_COMPRESSION_NAME: &str = "alt_bn128 G1 Compression";
+pub const G1_DECOMPRESSION_NAME: &str = "alt_bn128 G1 Decompression";
+pub const G2_COMPRESSION_NAME: &str = "alt_bn128 G2 Compression";
+pub const G2_DECOMPRESSION_NAME: &str = "alt_bn128 G2 Decompression";
+
+/// Size of a single (G1, G2) element of the pairing input in bytes
+const PAIRING_ELEMENT_SIZE: usize = ALT_BN128_G1_LEN + ALT_BN128_G2_LEN;
+
+/// Inputs of a compression operation with the output expected for each of them
+pub struct CompressionInputs {
+    op: u64,
+    pool: InputPool,
+    /// Expected outputs in the order the inputs were generated in, the pool cycles through them
+    expected: Vec<Vec<u8>>,
+}
+
+impl CompressionInputs {
+    /// Generates the inputs of the given compression operation from the G1 or G2 points of the
+    /// pairing input, after checking that every point survives the compression round trip
+    pub fn generate(limits: &PoolLimits, count: usize, op: u64) -> Result<Self, Error> {
+        let input = parse_hex(PAIRING_INPUT)?;
+        let (points, compress, decompress): (Vec<&[u8]>, AltBn128Compression, AltBn128Compression) =
+            match op {
+                ALT_BN128_G1_COMPRESS | ALT_BN128_G1_DECOMPRESS => (
+                    input
+                        .chunks(PAIRING_ELEMENT_SIZE)
+                        .map(|element| &element[..ALT_BN128_G1_LEN])
+                        .collect(),
+                    alt_bn128_g1_compress,
+                    alt_bn128_g1_decompress,
+                ),
+                ALT_BN128_G2_COMPRESS | ALT_BN128_G2_DECOMPRESS => (
+                    input
+                        .chunks(PAIRING_ELEMENT_SIZE)
+                        .map(|element| &element[ALT_BN128_G1_LEN..])
+                        .collect(),
+                    alt_bn128_g2_compress,
+                    alt_bn128_g2_decompress,
+                ),
+                _ => {
+                    return Err(Error::Input(format!(
+                        "Unknown compression operation {}",
+                        op
+                    )))
+                }
+            };
+
+        let mut uncompressed = Vec::new();
+        let mut compressed = Vec::new();
+        for point in points {
+            let compressed_point = compress(point).map_err(Error::AltBn128)?;
+            if decompress(&compressed_point).map_err(Error::AltBn128)? != point {
+                return Err(Error::Input(format!(
+                    "Decompression does not restore the point {}",
+                    array_bytes::bytes2hex("", point)
+                )));
+            }
+            uncompressed.push(point.to_vec());
+            compressed.push(compressed_point);
+        }
+        let (inputs, expected) = match op {
+            ALT_BN128_G1_COMPRESS | ALT_BN128_G2_COMPRESS => (uncompressed, compressed),
+            _ => (compressed, uncompressed),
+        };
+
+        let mut cycle = inputs.iter().cycle();
+        let pool = InputPool::generate(limits, count, inputs[0].len(), || {
+            cycle
+                .next()
+                .expect("No points in the pairing input")
+                .clone()
+        });
+        Ok(Self { op, pool, expected })
+    }
+
+    /// Moves every input the given number of bytes past the aligned start of its buffer
+    pub fn misalign(self, offset: usize) -> Self {
+        Self {
+            pool: self.pool.misalign(offset),
+            ..self
+        }
+    }
+
+    /// Name of the benchmark of the operation
+    pub fn name(&self) -> &'static str {
+        name(self.op)
+    }
+
+    /// Size of a single input in bytes
+    pub fn input_size(&self) -> usize {
+        self.pool.input_size()
+    }
+
+    /// Output expected for the input at the given index of the pool
+    fn expected(&self, index: usize) -> &[u8] {
+        &self.expected[index % self.expected.len()]
+    }
+}
+
+/// Name of the benchmark of the given compression operation
+fn name(op: u64) -> &'static str {
+    match op {
+        ALT_BN128_G1_COMPRESS => G1_COMPRESSION_NAME,
+        ALT_BN128_G1_DECOMPRESS => G1_DECOMPRESSION_NAME,
+        ALT_BN128_G2_COMPRESS => G2_COMPRESSION_NAME,
+        _ => G2_DECOMPRESSION_NAME,
+    }
+}
+
+/// Size of the output of the given compression operation in bytes
+fn output_len(op: u64) -> usize {
+    match op {
+        ALT_BN128_G1_COMPRESS => ALT_BN128_G1_COMPRESSED_LEN,
+        ALT_BN128_G1_DECOMPRESS => ALT_BN128_G1_LEN,
+        ALT_BN128_G2_COMPRESS => ALT_BN128_G2_COMPRESSED_LEN,
+        _ => ALT_BN128_G2_LEN,
+    }
+}
+
+/// Runs the alt bn128 Compression benchmark of the operation of the inputs, checks every output
+/// against the expected one and returns the measurement
+pub fn alt_bn128_bench_compression(
+    inputs: &CompressionInputs,
+    harness: &Harness,
+    k: Option<f64>,
+    budget: Option<u64>,
+    context: &SyscallContext,
+    alignment: &Alignment,
+) -> Result<Measurement, Error> {
+    let name = inputs.name();
+    let compute_meter = budget.map(|units| Rc::new(RefCell::new(ComputeMeter::new(units))));
+    let caller = match &compute_meter {
+        Some(compute_meter) => {
+            SyscallAltBn128Compression::with_compute_meter(context, compute_meter.clone())
+        }
+        None => SyscallAltBn128Compression::new(context),
+    };
+    let pool = &inputs.pool;
+    let mapping = PoolMapping::new(pool, context, alignment, output_len(inputs.op))?;
+    let input_size = pool.input_size() as u64;
+
+    measure_syscall(
+        name,
+        harness,
+        k,
+        |i| pool.index(i),
+        |i| {
+            let index = pool.index(i);
+            alt_bn128_run_compression(
+                &caller,
+                inputs.op,
+                mapping.memory_mapping(),
+                mapping.input_addr(index),
+                input_size,
+            )?;
+            if mapping.output() != inputs.expected(index) {
+                return Err(Error::Output(format!(
+                    "{} returned {}, expected {}",
+                    name,
+                    array_bytes::bytes2hex("", mapping.output()),
+                    array_bytes::bytes2hex("", inputs.expected(index))
+                )));
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Executes single alt_bn128 Compression call of the given operation on the input of the given
+/// size mapped at the given VM address, the output is written to `OUTPUT_VM_ADDR`
+#[inline]
+pub fn alt_bn128_run_compression(
+    syscall: &SyscallAltBn128Compression,
+    op: u64,
+    memory_mapping: &MemoryMapping,
+    input_addr: u64,
+    input_size: u64,
+) -> Result<(), Error> {
+    let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+    syscall.call(
+        op,
+        input_addr,
+        input_size,
+        OUTPUT_VM_ADDR,
+        0,
+        memory_mapping,
+        &mut result,
+    );
+
+    match result.map_err(Error::Syscall)? {
+        0 => Ok(()),
+        code => Err(Error::AltBn128(code.into())),
+    }
+}
@@ -1,66 +1,79 @@
-use super::PRECISION;
-use crate::alt_bn128::{BpfError, SyscallAltBn128Multiplication};
-use crate::significant;
-use cpu_time::ProcessTime;
-use solana_rbpf::vm::Config;
-use tracing::{error, info};
+use super::harness::{Harness, Measurement};
+use super::mapping::PoolMapping;
+use super::pool::InputPool;
+use super::{measure_syscall, Alignment, OUTPUT_VM_ADDR};
+use crate::alt_bn128::{BpfError, ComputeMeter, SyscallAltBn128Multiplication, SyscallContext};
+use crate::error::Error;
+use solana_rbpf::error::EbpfError;
+use solana_rbpf::memory_region::MemoryMapping;
+use solana_sdk::alt_bn128::prelude::ALT_BN128_MULTIPLICATION_OUTPUT_LEN;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-const BENCHMARK_NAME: &str = "alt_bn128 Multiplication";
+pub const BENCHMARK_NAME: &str = "alt_bn128 Multiplication";
 
-/// Runs the alt bn128 Multiplication benchmark
-pub fn alt_bn128_bench_multiplication(inputs: &[Vec<u8>], k: f64) {
-    info!("> Start {} benchmark...", BENCHMARK_NAME);
-    let caller = SyscallAltBn128Multiplication::new();
-    let config = Config::default();
+/// Runs the alt bn128 Multiplication benchmark and returns the measurement
+pub fn alt_bn128_bench_multiplication(
+    pool: &InputPool,
+    harness: &Harness,
+    k: Option<f64>,
+    budget: Option<u64>,
+    context: &SyscallContext,
+    alignment: &Alignment,
+) -> Result<Measurement, Error> {
+    let compute_meter = budget.map(|units| Rc::new(RefCell::new(ComputeMeter::new(units))));
+    let caller = match &compute_meter {
+        Some(compute_meter) => {
+            SyscallAltBn128Multiplication::with_compute_meter(context, compute_meter.clone())
+        }
+        None => SyscallAltBn128Multiplication::new(context),
+    };
+    let mapping = PoolMapping::new(
+        pool,
+        context,
+        alignment,
+        ALT_BN128_MULTIPLICATION_OUTPUT_LEN,
+    )?;
+    let input_size = pool.input_size() as u64;
 
-    let now = ProcessTime::try_now().expect("Getting process time failed");
-    for input in inputs {
-        alt_bn128_run_multiplication(&caller, &config, input.as_ref());
-    }
-    let d = now.try_elapsed().expect("Getting process time failed");
-
-    let nanos = d.as_nanos() as f64;
-    let total = nanos / 1E9;
-    let n = inputs.len() as f64;
-    let average = total / n;
-
-    info!("Finish {}", BENCHMARK_NAME);
-    info!(
-        "{} ({} executions) elapsed {} s.",
+    measure_syscall(
         BENCHMARK_NAME,
-        n,
-        significant::precision(total, PRECISION)
-    );
-    info!(
-        "{} average: {} s. = {} K",
-        BENCHMARK_NAME,
-        significant::precision(average, PRECISION),
-        significant::precision(average / k, PRECISION)
-    );
+        harness,
+        k,
+        |i| pool.index(i),
+        |i| {
+            alt_bn128_run_multiplication(
+                &caller,
+                mapping.memory_mapping(),
+                mapping.input_addr(pool.index(i)),
+                input_size,
+            )
+        },
+    )
 }
 
-/// Executes single alt_bn128 Multiplication call.
+/// Executes single alt_bn128 Multiplication call on the input of the given size mapped at the given VM
+/// address, the output is written to `OUTPUT_VM_ADDR`
 #[inline]
-fn alt_bn128_run_multiplication(
+pub fn alt_bn128_run_multiplication(
     syscall: &SyscallAltBn128Multiplication,
-    config: &Config,
-    input: &[u8],
-) {
-    use solana_rbpf::error::EbpfError;
-    use solana_rbpf::memory_region::{MemoryMapping, MemoryRegion};
-
-    let memory_mapping = MemoryMapping::new::<BpfError>(
-        vec![MemoryRegion::new_from_slice(input, 0, 0, true)],
-        config,
-    )
-    .unwrap();
-
+    memory_mapping: &MemoryMapping,
+    input_addr: u64,
+    input_size: u64,
+) -> Result<(), Error> {
     let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
-    syscall.call(0, input.len() as u64, 0, 0, 0, &memory_mapping, &mut result);
+    syscall.call(
+        input_addr,
+        input_size,
+        OUTPUT_VM_ADDR,
+        0,
+        0,
+        memory_mapping,
+        &mut result,
+    );
 
-    if let Err(err) = result {
-        error!("{:?}", err);
-        panic!("{:?}", err);
+    match result.map_err(Error::Syscall)? {
+        0 => Ok(()),
+        code => Err(Error::AltBn128(code.into())),
     }
-    assert_eq!(result.unwrap(), 0);
 }
@@ -0,0 +1,265 @@
+use super::harness::{Harness, Measurement};
+use super::{measure_call, parse_hex, separator, MULTIPLICATION_INPUT, PAIRING_INPUT, PRECISION};
+use crate::error::Error;
+use crate::significant;
+use ark_bn254::{Bn254, Fq, Fq12, Fq2, G1Affine, G2Affine};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{BigInteger256, One, PrimeField, Zero};
+use solana_sdk::alt_bn128::prelude::AltBn128Error;
+use std::convert::TryFrom;
+use tracing::info;
+
+const BENCHMARK_NAME: &str = "alt_bn128 points";
+
+pub const G1_DECODING: &str = "G1 decoding";
+pub const G1_SUBGROUP_CHECK: &str = "G1 subgroup check";
+pub const G2_DECODING: &str = "G2 decoding";
+pub const G2_SUBGROUP_CHECK: &str = "G2 subgroup check";
+pub const G2_ADDITION: &str = "G2 Addition";
+pub const G2_MULTIPLICATION: &str = "G2 Multiplication";
+pub const MILLER_LOOP: &str = "Miller loop";
+pub const FINAL_EXPONENTIATION: &str = "Final exponentiation";
+
+/// Size of an encoded field element in bytes
+const FIELD_SIZE: usize = 32;
+
+/// Size of an encoded G1 point in bytes
+const G1_SIZE: usize = 2 * FIELD_SIZE;
+
+/// Size of an encoded G2 point in bytes
+const G2_SIZE: usize = 4 * FIELD_SIZE;
+
+/// Size of a single (G1, G2) element of the pairing input in bytes
+const PAIRING_ELEMENT_SIZE: usize = G1_SIZE + G2_SIZE;
+
+/// Runs the benchmarks of the stages of the alt_bn128 operations on the points of the pairing
+/// input: decoding and on-curve check of the G1 and G2 points, their subgroup checks,
+/// G2 Addition and Multiplication, the Miller loop and the final exponentiation of the pairing.
+/// Returns the measurement and the input size of every stage.
+pub fn alt_bn128_points_bench(
+    harness: &Harness,
+    k: Option<f64>,
+) -> Result<Vec<(&'static str, usize, Measurement)>, Error> {
+    info!("> Start {} benchmarks...", BENCHMARK_NAME);
+    let input = parse_hex(PAIRING_INPUT)?;
+    let scalar = parse_hex(MULTIPLICATION_INPUT)?;
+    let scalar = be_bigint(&scalar[G1_SIZE..]);
+    let encoded: Vec<(&[u8], &[u8])> = input
+        .chunks(PAIRING_ELEMENT_SIZE)
+        .map(|element| element.split_at(G1_SIZE))
+        .collect();
+    let pairs = validate(&encoded)?;
+    let (g1, g2) = encoded[0];
+    let (p, q) = pairs[0];
+    let r = pairs[1].1;
+
+    let mut measurements = Vec::new();
+    let g1_decoding = measure_call(G1_DECODING, harness, k, |_| {
+        decode_g1(g1).map(|_| ()).map_err(Error::AltBn128)
+    })?;
+    measurements.push((G1_DECODING, G1_SIZE, g1_decoding));
+
+    separator();
+    let g1_subgroup_check = measure_call(G1_SUBGROUP_CHECK, harness, k, |_| {
+        check_subgroup_g1(&p).map_err(Error::AltBn128)
+    })?;
+    measurements.push((G1_SUBGROUP_CHECK, G1_SIZE, g1_subgroup_check));
+
+    separator();
+    let g2_decoding = measure_call(G2_DECODING, harness, k, |_| {
+        decode_g2(g2).map(|_| ()).map_err(Error::AltBn128)
+    })?;
+    measurements.push((G2_DECODING, G2_SIZE, g2_decoding));
+
+    separator();
+    let g2_subgroup_check = measure_call(G2_SUBGROUP_CHECK, harness, k, |_| {
+        check_subgroup_g2(&q).map_err(Error::AltBn128)
+    })?;
+    measurements.push((G2_SUBGROUP_CHECK, G2_SIZE, g2_subgroup_check));
+
+    separator();
+    let g2_addition = measure_call(G2_ADDITION, harness, k, |_| {
+        let mut sum = q.into_projective();
+        sum.add_assign_mixed(&r);
+        non_zero(&sum)
+    })?;
+    measurements.push((G2_ADDITION, 2 * G2_SIZE, g2_addition));
+
+    separator();
+    let g2_multiplication =
+        measure_call(G2_MULTIPLICATION, harness, k, |_| non_zero(&q.mul(scalar)))?;
+    measurements.push((G2_MULTIPLICATION, G2_SIZE + FIELD_SIZE, g2_multiplication));
+
+    separator();
+    let mut f = Fq12::one();
+    let miller_loop = measure_call(MILLER_LOOP, harness, k, |_| {
+        f = miller_loop_of(&pairs);
+        Ok(())
+    })?;
+    measurements.push((MILLER_LOOP, input.len(), miller_loop));
+
+    separator();
+    let final_exponentiation = measure_call(FINAL_EXPONENTIATION, harness, k, |_| {
+        if !is_one(Bn254::final_exponentiation(&f)) {
+            return Err(Error::AltBn128(AltBn128Error::UnexpectedError));
+        }
+        Ok(())
+    })?;
+    measurements.push((FINAL_EXPONENTIATION, input.len(), final_exponentiation));
+
+    separator();
+    info!("Finish {}", BENCHMARK_NAME);
+    Ok(measurements)
+}
+
+/// Logs the alt_bn128 Pairing time of the pairing input decomposed into the validation of its
+/// points, the Miller loop and the final exponentiation, and how much of the Pairing syscall
+/// average they account for if it was measured
+pub fn pairing_report(measurements: &[(&str, usize, Measurement)], pairing: Option<f64>) {
+    let average = |name: &str| {
+        measurements
+            .iter()
+            .find(|(n, _, _)| *n == name)
+            .map(|(_, _, measurement)| measurement.average())
+            .unwrap_or_default()
+    };
+    let pairs = PAIRING_INPUT.len() / 2 / PAIRING_ELEMENT_SIZE;
+    let validation = pairs as f64
+        * (average(G1_DECODING)
+            + average(G1_SUBGROUP_CHECK)
+            + average(G2_DECODING)
+            + average(G2_SUBGROUP_CHECK));
+    let miller_loop = average(MILLER_LOOP);
+    let final_exponentiation = average(FINAL_EXPONENTIATION);
+    let total = validation + miller_loop + final_exponentiation;
+    let share = |time: f64| significant::precision(time / total * 100., PRECISION);
+
+    info!(
+        "Pairing of {} pairs takes {} s.: validation {} s. ({}%), Miller loop {} s. ({}%), final exponentiation {} s. ({}%)",
+        pairs,
+        significant::precision(total, PRECISION),
+        significant::precision(validation, PRECISION),
+        share(validation),
+        significant::precision(miller_loop, PRECISION),
+        share(miller_loop),
+        significant::precision(final_exponentiation, PRECISION),
+        share(final_exponentiation)
+    );
+    if let Some(pairing) = pairing {
+        info!(
+            "The stages add up to {}% of the alt_bn128 Pairing average {} s.",
+            significant::precision(total / pairing * 100., PRECISION),
+            significant::precision(pairing, PRECISION)
+        );
+    }
+}
+
+/// Decodes and checks the points of the pairing input and that their pairing product is one
+fn validate(encoded: &[(&[u8], &[u8])]) -> Result<Vec<(G1Affine, G2Affine)>, Error> {
+    let pairs = encoded
+        .iter()
+        .map(|(g1, g2)| {
+            let p = decode_g1(g1)?;
+            let q = decode_g2(g2)?;
+            check_subgroup_g1(&p)?;
+            check_subgroup_g2(&q)?;
+            Ok((p, q))
+        })
+        .collect::<Result<Vec<_>, AltBn128Error>>()
+        .map_err(Error::AltBn128)?;
+    if !is_one(Bn254::final_exponentiation(&miller_loop_of(&pairs))) {
+        return Err(Error::Input(
+            "Pairing product of the pairing input is not one".to_owned(),
+        ));
+    }
+    Ok(pairs)
+}
+
+/// Big-endian encoded 256-bit integer
+fn be_bigint(bytes: &[u8]) -> BigInteger256 {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks(8)) {
+        *limb = u64::from_be_bytes(<[u8; 8]>::try_from(chunk).expect("Invalid limb size"));
+    }
+    BigInteger256(limbs)
+}
+
+/// Decodes a big-endian field element, fails if it is not less than the modulus
+fn decode_fq(bytes: &[u8]) -> Result<Fq, AltBn128Error> {
+    Fq::from_repr(be_bigint(bytes)).ok_or(AltBn128Error::InvalidInputData)
+}
+
+/// Decodes a G1 point encoded as its x and y coordinates and checks that it is on the curve,
+/// all zeros encode the point at infinity
+pub fn decode_g1(bytes: &[u8]) -> Result<G1Affine, AltBn128Error> {
+    if bytes.iter().all(|byte| *byte == 0) {
+        return Ok(G1Affine::zero());
+    }
+    let x = decode_fq(&bytes[..FIELD_SIZE])?;
+    let y = decode_fq(&bytes[FIELD_SIZE..G1_SIZE])?;
+    let point = G1Affine::new(x, y, false);
+    if !point.is_on_curve() {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+    Ok(point)
+}
+
+/// Decodes a G2 point encoded as its x and y coordinates with the imaginary part of each first,
+/// as in EIP-197, and checks that it is on the curve. All zeros encode the point at infinity
+pub fn decode_g2(bytes: &[u8]) -> Result<G2Affine, AltBn128Error> {
+    if bytes.iter().all(|byte| *byte == 0) {
+        return Ok(G2Affine::zero());
+    }
+    let field = |i: usize| decode_fq(&bytes[i * FIELD_SIZE..(i + 1) * FIELD_SIZE]);
+    let x = Fq2::new(field(1)?, field(0)?);
+    let y = Fq2::new(field(3)?, field(2)?);
+    let point = G2Affine::new(x, y, false);
+    if !point.is_on_curve() {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+    Ok(point)
+}
+
+/// Checks that a G1 point on the curve is in the prime order subgroup. The cofactor of G1 is one,
+/// but the backend multiplies by the group order all the same.
+pub fn check_subgroup_g1(point: &G1Affine) -> Result<(), AltBn128Error> {
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(AltBn128Error::GroupError);
+    }
+    Ok(())
+}
+
+/// Checks that a G2 point on the curve is in the prime order subgroup
+pub fn check_subgroup_g2(point: &G2Affine) -> Result<(), AltBn128Error> {
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(AltBn128Error::GroupError);
+    }
+    Ok(())
+}
+
+/// Miller loop of the pairs including the preparation of their line coefficients
+fn miller_loop_of(pairs: &[(G1Affine, G2Affine)]) -> Fq12 {
+    let prepared: Vec<_> = pairs
+        .iter()
+        .map(|(p, q)| {
+            (
+                <Bn254 as PairingEngine>::G1Prepared::from(*p),
+                <Bn254 as PairingEngine>::G2Prepared::from(*q),
+            )
+        })
+        .collect();
+    Bn254::miller_loop(prepared.iter())
+}
+
+/// Checks the result of the final exponentiation of a successful pairing check
+fn is_one(result: Option<Fq12>) -> bool {
+    matches!(result, Some(result) if result.is_one())
+}
+
+/// Fails on the point at infinity, keeps the result of a G2 operation in use
+fn non_zero<P: ProjectiveCurve>(point: &P) -> Result<(), Error> {
+    if point.is_zero() {
+        return Err(Error::AltBn128(AltBn128Error::UnexpectedError));
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use super::harness::{Harness, Measurement};
+use super::pool::InputPool;
+use super::PRECISION;
+use crate::error::Error;
+use crate::significant;
+use tracing::info;
+
+const BENCHMARK_NAME: &str = "blake3";
+
+/// Runs the blake3 benchmark and returns the measurement
+pub fn blake3_bench(pool: &InputPool, harness: &Harness) -> Result<Measurement, Error> {
+    info!("> Start {} benchmark...", BENCHMARK_NAME);
+
+    let measurement = harness.measure(|i| {
+        blake3_run(pool.get(i));
+        Ok(true)
+    })?;
+
+    let total = measurement.total;
+    let n = measurement.executed as f64;
+    let average = measurement.average();
+
+    info!("Finish {}", BENCHMARK_NAME);
+    info!(
+        "{} ({} executions) elapsed {} s.",
+        BENCHMARK_NAME,
+        n,
+        significant::precision(total, PRECISION)
+    );
+    info!(
+        "{} average: {} s. ({})",
+        BENCHMARK_NAME,
+        significant::precision(average, PRECISION),
+        harness
+    );
+
+    Ok(measurement)
+}
+
+/// Executes single blake3 call
+#[inline]
+fn blake3_run(msg: &[u8]) {
+    let _ = blake3::hash(msg);
+}
@@ -0,0 +1,124 @@
+use crate::error::Error;
+use crate::options::ClockKind;
+use cpu_time::{ProcessTime, ThreadTime};
+use std::time::{Duration, Instant};
+
+/// Number of clock ticks observed to find out the clock resolution
+const RESOLUTION_SAMPLES: usize = 16;
+
+/// Time spent calibrating the cycle counter
+#[cfg(target_arch = "x86_64")]
+const CALIBRATION_TIME: Duration = Duration::from_millis(100);
+
+/// Clock measuring the elapsed time of the benchmarks
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    /// Kind of the clock
+    pub kind: ClockKind,
+    /// Cycle counter ticks per second
+    tsc_frequency: f64,
+    /// Smallest non-zero time step the clock measured when created
+    resolution: Duration,
+}
+
+/// Reading of a clock
+pub enum Reading {
+    Process(ProcessTime),
+    Thread(ThreadTime),
+    Wall(Instant),
+    Tsc(u64),
+}
+
+impl Clock {
+    /// Creates the clock, calibrates the cycle counter if needed and measures the resolution
+    pub fn new(kind: ClockKind) -> Result<Self, Error> {
+        let tsc_frequency = match kind {
+            ClockKind::Tsc => calibrate_tsc()?,
+            _ => 0.,
+        };
+        let mut clock = Self {
+            kind,
+            tsc_frequency,
+            resolution: Duration::default(),
+        };
+        clock.resolution = clock.measure_resolution()?;
+        Ok(clock)
+    }
+
+    /// Smallest non-zero time step the clock is able to measure
+    pub fn resolution(&self) -> Duration {
+        self.resolution
+    }
+
+    /// Reads the clock
+    pub fn now(&self) -> Result<Reading, Error> {
+        Ok(match self.kind {
+            ClockKind::Process => Reading::Process(ProcessTime::try_now().map_err(clock_error)?),
+            ClockKind::Thread => Reading::Thread(ThreadTime::try_now().map_err(clock_error)?),
+            ClockKind::Wall => Reading::Wall(Instant::now()),
+            ClockKind::Tsc => Reading::Tsc(rdtsc()),
+        })
+    }
+
+    /// Time elapsed since the given reading
+    pub fn elapsed(&self, reading: &Reading) -> Result<Duration, Error> {
+        Ok(match reading {
+            Reading::Process(now) => now.try_elapsed().map_err(clock_error)?,
+            Reading::Thread(now) => now.try_elapsed().map_err(clock_error)?,
+            Reading::Wall(now) => now.elapsed(),
+            Reading::Tsc(now) => {
+                let ticks = rdtsc().wrapping_sub(*now);
+                Duration::from_secs_f64(ticks as f64 / self.tsc_frequency)
+            }
+        })
+    }
+
+    /// Finds the smallest non-zero time step of the clock over a few ticks
+    fn measure_resolution(&self) -> Result<Duration, Error> {
+        let steps = (0..RESOLUTION_SAMPLES)
+            .map(|_| {
+                let now = self.now()?;
+                loop {
+                    let d = self.elapsed(&now)?;
+                    if d > Duration::default() {
+                        break Ok(d);
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(steps.into_iter().min().unwrap_or_default())
+    }
+}
+
+/// Reads the CPU cycle counter
+#[cfg(target_arch = "x86_64")]
+fn rdtsc() -> u64 {
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn rdtsc() -> u64 {
+    unreachable!("Cycle counter clock is not created on this architecture")
+}
+
+/// Measures the cycle counter frequency against the monotonic clock
+#[cfg(target_arch = "x86_64")]
+fn calibrate_tsc() -> Result<f64, Error> {
+    let start = Instant::now();
+    let ticks = rdtsc();
+    while start.elapsed() < CALIBRATION_TIME {}
+    let ticks = rdtsc().wrapping_sub(ticks);
+    Ok(ticks as f64 / start.elapsed().as_secs_f64())
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn calibrate_tsc() -> Result<f64, Error> {
+    Err(Error::Clock(
+        "Cycle counter clock is supported on x86_64 only".to_owned(),
+    ))
+}
+
+/// Error of reading the CPU time of the process or the thread
+fn clock_error(error: std::io::Error) -> Error {
+    Error::Clock(error.to_string())
+}
@@ -0,0 +1,57 @@
+use super::PRECISION;
+use crate::options::Baseline;
+use crate::significant;
+use tracing::{info, warn};
+
+/// Base compute units of a hash syscall, equal for all the baselines
+const HASH_BASE_COST: u64 = 85;
+
+/// Compute units of a hash syscall per two bytes of input
+const HASH_BYTE_COST: u64 = 1;
+
+/// Compute units charged for a hash of the given size
+pub fn hash_cost(size: usize) -> u64 {
+    HASH_BASE_COST + HASH_BYTE_COST * (size as u64 / 2)
+}
+
+/// Reports the equivalent compute units of every operation as derived from every baseline,
+/// and flags the operations where the baselines disagree by more than the tolerance
+pub fn consistency_report(
+    operations: &[(&str, f64)],
+    baselines: &[(Baseline, f64)],
+    size: usize,
+    tolerance: f64,
+) {
+    info!("> Consistency of equivalent compute units across baselines");
+    let cost = hash_cost(size) as f64;
+
+    for (name, average) in operations {
+        let units: Vec<f64> = baselines.iter().map(|(_, k)| average / k * cost).collect();
+        for ((baseline, _), units) in baselines.iter().zip(&units) {
+            info!(
+                "{} relative to {}: {} units",
+                name,
+                baseline,
+                significant::precision(*units, PRECISION)
+            );
+        }
+
+        let min = units.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = units.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mean = units.iter().sum::<f64>() / units.len() as f64;
+        let spread = (max - min) / mean;
+        if spread > tolerance {
+            warn!(
+                "{} disagrees across baselines by {}%, the host machine is not representative of validator hardware",
+                name,
+                significant::precision(spread * 100., PRECISION)
+            );
+        } else {
+            info!(
+                "{} is consistent across baselines, spread {}%",
+                name,
+                significant::precision(spread * 100., PRECISION)
+            );
+        }
+    }
+}
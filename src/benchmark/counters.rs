@@ -0,0 +1,149 @@
+use std::ops::AddAssign;
+
+/// Hardware events counted around the measured rounds
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CounterValues {
+    pub cycles: u64,
+    pub instructions: u64,
+    pub branch_misses: u64,
+    pub cache_misses: u64,
+}
+
+impl AddAssign for CounterValues {
+    fn add_assign(&mut self, other: Self) {
+        self.cycles += other.cycles;
+        self.instructions += other.instructions;
+        self.branch_misses += other.branch_misses;
+        self.cache_misses += other.cache_misses;
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub use self::linux::Counters;
+
+#[cfg(not(target_os = "linux"))]
+pub use self::unsupported::Counters;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::CounterValues;
+    use perf_event_open_sys as sys;
+    use perf_event_open_sys::bindings::{self, perf_event_attr};
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    /// Hardware events opened in this order, the first one leads the group
+    const EVENTS: [u32; 4] = [
+        bindings::perf_hw_id_PERF_COUNT_HW_CPU_CYCLES,
+        bindings::perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+        bindings::perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES,
+        bindings::perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+    ];
+
+    /// Group of hardware counters of the calling thread, user space only
+    pub struct Counters {
+        events: Vec<File>,
+    }
+
+    impl Counters {
+        /// Opens the counters, fails when the kernel or the hardware does not allow it
+        pub fn open() -> Result<Self, String> {
+            let mut events: Vec<File> = Vec::with_capacity(EVENTS.len());
+            for &event in EVENTS.iter() {
+                let group = events.first().map_or(-1, |leader| leader.as_raw_fd());
+                let file = open_event(event, group).map_err(|e| match e.kind() {
+                    io::ErrorKind::PermissionDenied => format!(
+                        "{} (check /proc/sys/kernel/perf_event_paranoid or CAP_PERFMON)",
+                        e
+                    ),
+                    _ => e.to_string(),
+                })?;
+                events.push(file);
+            }
+            Ok(Self { events })
+        }
+
+        /// Resets and starts the counters
+        pub fn start(&self) -> Result<(), String> {
+            self.group_ioctl(sys::ioctls::RESET)?;
+            self.group_ioctl(sys::ioctls::ENABLE)
+        }
+
+        /// Stops the counters and returns their values
+        pub fn stop(&mut self) -> Result<CounterValues, String> {
+            self.group_ioctl(sys::ioctls::DISABLE)?;
+            let mut values = [0u64; 4];
+            for (value, event) in values.iter_mut().zip(self.events.iter_mut()) {
+                let mut buf = [0u8; 8];
+                event
+                    .read_exact(&mut buf)
+                    .map_err(|e| format!("Reading hardware counter failed: {}", e))?;
+                *value = u64::from_ne_bytes(buf);
+            }
+            Ok(CounterValues {
+                cycles: values[0],
+                instructions: values[1],
+                branch_misses: values[2],
+                cache_misses: values[3],
+            })
+        }
+
+        /// Applies the ioctl to the whole group through its leader
+        fn group_ioctl(&self, ioctl: unsafe fn(i32, u32) -> i32) -> Result<(), String> {
+            let leader = self.events[0].as_raw_fd();
+            let result =
+                unsafe { ioctl(leader, bindings::perf_event_ioc_flags_PERF_IOC_FLAG_GROUP) };
+            if result == -1 {
+                return Err(format!(
+                    "Hardware counter ioctl failed: {}",
+                    io::Error::last_os_error()
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    /// Opens a disabled hardware event counting the calling thread on any CPU
+    fn open_event(event: u32, group: i32) -> io::Result<File> {
+        let mut attr = perf_event_attr {
+            type_: bindings::perf_type_id_PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<perf_event_attr>() as u32,
+            config: event as u64,
+            ..Default::default()
+        };
+        attr.set_disabled(if group == -1 { 1 } else { 0 });
+        attr.set_exclude_kernel(1);
+        attr.set_exclude_hv(1);
+
+        let fd = unsafe {
+            sys::perf_event_open(&mut attr, 0, -1, group, bindings::PERF_FLAG_FD_CLOEXEC as _)
+        };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { File::from_raw_fd(fd) })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod unsupported {
+    use super::CounterValues;
+
+    /// Hardware counters are not available on this platform
+    pub struct Counters;
+
+    impl Counters {
+        pub fn open() -> Result<Self, String> {
+            Err("hardware counters are supported on Linux only".to_owned())
+        }
+
+        pub fn start(&self) -> Result<(), String> {
+            Ok(())
+        }
+
+        pub fn stop(&mut self) -> Result<CounterValues, String> {
+            Ok(CounterValues::default())
+        }
+    }
+}
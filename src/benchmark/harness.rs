@@ -0,0 +1,415 @@
+use super::clock::Clock;
+use super::counters::{CounterValues, Counters};
+use super::PRECISION;
+use crate::alt_bn128::alt_bn128_error_name;
+use crate::error::Error;
+use crate::options::CacheMode;
+use crate::report::{CounterAverages, Failure};
+use crate::{significant, statistics};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use tracing::info;
+
+/// Size of a cache line touched by the eviction
+const CACHE_LINE: usize = 64;
+
+/// Number of batches the rounds are split into, each batch gives a sample
+const SAMPLES: usize = 30;
+
+/// Minimum number of samples before the confidence interval is trusted
+const MIN_SAMPLES: usize = 10;
+
+/// Share of the target time spent estimating the cost of a round
+const PILOT_SHARE: f64 = 0.05;
+
+/// Measurement settings shared by all the benchmarks
+#[derive(Clone, Debug)]
+pub struct Harness {
+    /// Number of rounds, upper bound of the rounds if precision is set
+    pub count: usize,
+    /// Target duration of a measurement, overrides the number of rounds
+    pub time: Option<Duration>,
+    /// Relative half-width of the 95% confidence interval to stop at
+    pub precision: Option<f64>,
+    /// Cache residency of the data between the rounds
+    pub cache: CacheMode,
+    /// Size of the scratch buffer touched between the rounds in cache-cold mode
+    pub scratch_size: usize,
+    /// Clock measuring the elapsed time
+    pub clock: Clock,
+    /// Collect hardware performance counters around the timed rounds,
+    /// the measurement fails if they cannot be opened
+    pub counters: bool,
+    /// Record the inputs failing a round and continue instead of stopping the run
+    pub keep_going: bool,
+}
+
+/// Result of a measurement
+pub struct Measurement {
+    /// Number of executed rounds
+    pub executed: usize,
+    /// Total elapsed time in seconds
+    pub total: f64,
+    /// Average elapsed time of a round in every batch in seconds
+    pub samples: Vec<f64>,
+    /// Inputs failing a round, empty unless the harness keeps going
+    pub failures: Vec<Failure>,
+    /// Number of rounds failing with every alt_bn128 error, by the error name,
+    /// empty unless the harness keeps going
+    pub errors: BTreeMap<String, usize>,
+    /// Hardware events per round, none unless the counters are collected
+    pub counters: Option<CounterAverages>,
+}
+
+/// Inputs failing the rounds of a measurement and the alt_bn128 errors they returned,
+/// recorded when the harness keeps going, the first failure stops the measurement otherwise
+#[derive(Default)]
+pub struct Failures {
+    /// Failed inputs by their index in the pool
+    inputs: BTreeMap<usize, Failure>,
+    /// Number of failed rounds by the alt_bn128 error name
+    errors: BTreeMap<String, usize>,
+}
+
+impl Measurement {
+    /// Average elapsed time of a round in seconds
+    pub fn average(&self) -> f64 {
+        self.total / self.executed as f64
+    }
+}
+
+impl Failures {
+    /// Records the failed round on the given input and continues if the harness keeps going,
+    /// returns the error otherwise
+    pub fn record(&mut self, harness: &Harness, input: usize, error: Error) -> Result<bool, Error> {
+        if !harness.keep_going {
+            return Err(error);
+        }
+        if let Error::AltBn128(error) = &error {
+            *self
+                .errors
+                .entry(alt_bn128_error_name(error).to_owned())
+                .or_default() += 1;
+        }
+        self.inputs
+            .entry(input)
+            .or_insert_with(|| Failure {
+                input,
+                rounds: 0,
+                error: error.to_string(),
+            })
+            .rounds += 1;
+        Ok(true)
+    }
+
+    /// Attaches the failed inputs in the order of the pool and the error counts to the measurement
+    fn attach(self, measurement: &mut Measurement) {
+        measurement.failures = self.inputs.into_values().collect();
+        measurement.errors = self.errors;
+    }
+}
+
+impl Harness {
+    /// Measures up to `count` rounds of `f`, which gets the round index
+    /// and returns `false` to stop early. In cache-cold mode the caches are
+    /// evicted outside of the timed region before every round.
+    pub fn measure<F>(&self, mut f: F) -> Result<Measurement, Error>
+    where
+        F: FnMut(usize) -> Result<bool, Error>,
+    {
+        self.measure_recording(|i, _| f(i))
+    }
+
+    /// Measures the rounds of `f` like `measure`, `f` also gets the failures to record the
+    /// failed rounds in. Only the failures of the measured rounds are attached to the
+    /// measurement, the errors of the pilot run are logged on their own.
+    pub fn measure_recording<F>(&self, mut f: F) -> Result<Measurement, Error>
+    where
+        F: FnMut(usize, &mut Failures) -> Result<bool, Error>,
+    {
+        let mut scratch = match self.cache {
+            CacheMode::Hot => vec![],
+            CacheMode::Cold => vec![0u8; self.scratch_size],
+        };
+        let count = match self.time {
+            Some(time) => {
+                let mut pilot = Failures::default();
+                let count = self.pilot(time, &mut scratch, &mut |i| f(i, &mut pilot))?;
+                for (error, rounds) in &pilot.errors {
+                    info!(
+                        "Pilot run returned {} in {} rounds, not counted",
+                        error, rounds
+                    );
+                }
+                count
+            }
+            None => self.count,
+        };
+        let mut failures = Failures::default();
+        let mut round = |i| f(i, &mut failures);
+        let batch = (count / SAMPLES).max(1);
+
+        let mut counters = if self.counters {
+            Some(Counters::open().map_err(Error::Counters)?)
+        } else {
+            None
+        };
+        let mut events = CounterValues::default();
+
+        let mut executed = 0;
+        let mut d = Duration::default();
+        let mut samples = Vec::with_capacity(SAMPLES + 1);
+        let mut proceed = true;
+        while proceed && executed < count {
+            let rounds = batch.min(count - executed);
+            let (done, elapsed) = self.batch(
+                executed,
+                rounds,
+                &mut scratch,
+                &mut counters,
+                &mut events,
+                &mut round,
+            )?;
+            proceed = done == rounds;
+            executed += done;
+            d += elapsed;
+            if done > 0 {
+                samples.push(elapsed.as_nanos() as f64 / 1E9 / done as f64);
+            }
+
+            if let Some(precision) = self.precision {
+                if samples.len() >= MIN_SAMPLES
+                    && statistics::relative_confidence(&samples) <= precision
+                {
+                    info!(
+                        "Confidence interval reached ±{}% after {} rounds",
+                        significant::precision(precision * 100., PRECISION),
+                        executed
+                    );
+                    break;
+                }
+            }
+        }
+
+        if samples.len() > 1 {
+            info!(
+                "{} samples, 95% confidence interval ±{}%",
+                samples.len(),
+                significant::precision(statistics::relative_confidence(&samples) * 100., PRECISION)
+            );
+        }
+
+        let averages = counters.map(|_| {
+            let per_round = |value: u64| value as f64 / executed.max(1) as f64;
+            CounterAverages {
+                cycles: per_round(events.cycles),
+                instructions: per_round(events.instructions),
+                branch_misses: per_round(events.branch_misses),
+                cache_misses: per_round(events.cache_misses),
+            }
+        });
+        if let Some(averages) = &averages {
+            info!(
+                "Per round: {} cycles, {} instructions, {} branch misses, {} cache misses",
+                significant::precision(averages.cycles, PRECISION),
+                significant::precision(averages.instructions, PRECISION),
+                significant::precision(averages.branch_misses, PRECISION),
+                significant::precision(averages.cache_misses, PRECISION)
+            );
+        }
+
+        let mut measurement = Measurement {
+            executed,
+            total: d.as_nanos() as f64 / 1E9,
+            samples,
+            failures: Vec::new(),
+            errors: BTreeMap::new(),
+            counters: averages,
+        };
+        failures.attach(&mut measurement);
+        Ok(measurement)
+    }
+
+    /// Runs the given number of rounds starting from `first`,
+    /// returns the number of completed rounds and their elapsed time,
+    /// adds the hardware events of the timed region to `events`
+    fn batch<F: FnMut(usize) -> Result<bool, Error>>(
+        &self,
+        first: usize,
+        rounds: usize,
+        scratch: &mut [u8],
+        counters: &mut Option<Counters>,
+        events: &mut CounterValues,
+        f: &mut F,
+    ) -> Result<(usize, Duration), Error> {
+        let mut done = 0;
+        match self.cache {
+            CacheMode::Hot => {
+                start(counters)?;
+                let now = self.clock.now()?;
+                while done < rounds && f(first + done)? {
+                    done += 1;
+                }
+                let elapsed = self.clock.elapsed(&now)?;
+                stop(counters, events)?;
+                Ok((done, elapsed))
+            }
+            CacheMode::Cold => {
+                let mut d = Duration::default();
+                while done < rounds {
+                    evict(scratch);
+                    start(counters)?;
+                    let now = self.clock.now()?;
+                    let proceed = f(first + done)?;
+                    d += self.clock.elapsed(&now)?;
+                    stop(counters, events)?;
+                    if !proceed {
+                        break;
+                    }
+                    done += 1;
+                }
+                Ok((done, d))
+            }
+        }
+    }
+
+    /// Estimates the cost of a round and returns the number of rounds filling the target time.
+    /// The rounds are run like the measured ones, in cache-cold mode the caches are evicted
+    /// outside of the timed region before every round.
+    fn pilot<F>(&self, time: Duration, scratch: &mut [u8], f: &mut F) -> Result<usize, Error>
+    where
+        F: FnMut(usize) -> Result<bool, Error>,
+    {
+        let budget = time.mul_f64(PILOT_SHARE);
+        let mut events = CounterValues::default();
+        let mut rounds = 0;
+        let mut d = Duration::default();
+        while d < budget {
+            let (done, elapsed) = self.batch(rounds, 1, scratch, &mut None, &mut events, f)?;
+            d += elapsed;
+            if done == 0 {
+                break;
+            }
+            rounds += done;
+        }
+        let cost = d.as_secs_f64() / rounds.max(1) as f64;
+        let count = (time.as_secs_f64() / cost).ceil() as usize;
+        info!(
+            "Pilot run of {} rounds, {} s. per round, {} rounds fill {} s.",
+            rounds,
+            significant::precision(cost, PRECISION),
+            count,
+            time.as_secs_f64()
+        );
+        Ok(count.max(1))
+    }
+}
+
+impl fmt::Display for Harness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cache {}, clock {}", self.cache, self.clock.kind)?;
+        if self.counters {
+            write!(f, ", counters")?;
+        }
+        Ok(())
+    }
+}
+
+/// Touches every cache line of the scratch buffer to push everything else out of the caches
+fn evict(scratch: &mut [u8]) {
+    for i in (0..scratch.len()).step_by(CACHE_LINE) {
+        scratch[i] = scratch[i].wrapping_add(1);
+    }
+}
+
+/// Starts the hardware counters if they are collected
+fn start(counters: &Option<Counters>) -> Result<(), Error> {
+    if let Some(counters) = counters {
+        counters.start().map_err(Error::Counters)?;
+    }
+    Ok(())
+}
+
+/// Stops the hardware counters if they are collected and adds their values to `events`
+fn stop(counters: &mut Option<Counters>, events: &mut CounterValues) -> Result<(), Error> {
+    if let Some(counters) = counters {
+        *events += counters.stop().map_err(Error::Counters)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::ClockKind;
+    use solana_sdk::alt_bn128::prelude::AltBn128Error;
+
+    fn harness(keep_going: bool) -> Harness {
+        Harness {
+            count: 1_000_000,
+            time: Some(Duration::from_millis(20)),
+            precision: None,
+            cache: CacheMode::Hot,
+            scratch_size: 0,
+            clock: Clock::new(ClockKind::Wall).unwrap(),
+            counters: false,
+            keep_going,
+        }
+    }
+
+    #[test]
+    fn failures_of_the_pilot_run_are_not_counted() {
+        let harness = harness(true);
+        let mut calls = 0;
+        let measurement = harness
+            .measure_recording(|_, failures| {
+                calls += 1;
+                failures.record(
+                    &harness,
+                    0,
+                    Error::AltBn128(AltBn128Error::InvalidInputData),
+                )
+            })
+            .unwrap();
+        assert!(calls > measurement.executed);
+        assert_eq!(measurement.failures.len(), 1);
+        assert_eq!(measurement.failures[0].rounds, measurement.executed);
+        assert_eq!(
+            measurement.errors.values().copied().collect::<Vec<_>>(),
+            vec![measurement.executed]
+        );
+    }
+
+    #[test]
+    fn errors_are_not_counted_without_keep_going() {
+        let harness = harness(false);
+        let mut failures = Failures::default();
+        let error = failures.record(
+            &harness,
+            0,
+            Error::AltBn128(AltBn128Error::InvalidInputData),
+        );
+        assert!(matches!(
+            error,
+            Err(Error::AltBn128(AltBn128Error::InvalidInputData))
+        ));
+        assert!(failures.inputs.is_empty());
+        assert!(failures.errors.is_empty());
+    }
+
+    #[test]
+    fn counters_failing_to_open_fail_the_measurement() {
+        let harness = Harness {
+            time: None,
+            count: 10,
+            counters: true,
+            ..harness(false)
+        };
+        let measurement = harness.measure(|_| Ok(true));
+        match Counters::open() {
+            Ok(_) => assert!(measurement.unwrap().counters.is_some()),
+            Err(e) => assert!(matches!(measurement, Err(Error::Counters(error)) if error == e)),
+        }
+    }
+}
@@ -1,25 +1,25 @@
+use super::harness::{Harness, Measurement};
+use super::pool::InputPool;
 use super::PRECISION;
+use crate::error::Error;
 use crate::significant;
-use cpu_time::ProcessTime;
 use solana_sdk::keccak;
 use tracing::info;
 
-const BENCHMARK_NAME: &str = "keccak256";
+pub const BENCHMARK_NAME: &str = "keccak256";
 
-/// Runs the keccak benchmark and returns the average elapsed time
-pub fn keccak_bench(buffers: &[Vec<u8>]) -> f64 {
+/// Runs the keccak benchmark and returns the measurement
+pub fn keccak_bench(pool: &InputPool, harness: &Harness) -> Result<Measurement, Error> {
     info!("> Start {} benchmark...", BENCHMARK_NAME);
 
-    let now = ProcessTime::try_now().expect("Getting process time failed");
-    for b in buffers {
-        keccak_run(b);
-    }
-    let d = now.try_elapsed().expect("Getting process time failed");
+    let measurement = harness.measure(|i| {
+        keccak_run(pool.get(i));
+        Ok(true)
+    })?;
 
-    let nanos = d.as_nanos() as f64;
-    let total = nanos / 1E9;
-    let n = buffers.len() as f64;
-    let average = total / n;
+    let total = measurement.total;
+    let n = measurement.executed as f64;
+    let average = measurement.average();
 
     info!("Finish {}", BENCHMARK_NAME);
     info!(
@@ -29,16 +29,17 @@ pub fn keccak_bench(buffers: &[Vec<u8>]) -> f64 {
         significant::precision(total, PRECISION)
     );
     info!(
-        "{} average: {} s.",
+        "{} average: {} s. ({})",
         BENCHMARK_NAME,
-        significant::precision(average, PRECISION)
+        significant::precision(average, PRECISION),
+        harness
     );
 
-    average
+    Ok(measurement)
 }
 
 /// Executes single keccak256 call
 #[inline]
-fn keccak_run(msg: &[u8]) {
+pub fn keccak_run(msg: &[u8]) {
     let _ = keccak::hash(msg);
 }
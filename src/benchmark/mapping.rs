@@ -0,0 +1,145 @@
+use super::harness::{Harness, Measurement};
+use super::pool::InputPool;
+use super::{measure_call, separator, Alignment, OUTPUT_VM_ADDR};
+use crate::alt_bn128::{translate_slice, BpfError, SyscallContext};
+use crate::error::Error;
+use solana_rbpf::memory_region::{MemoryMapping, MemoryRegion};
+use solana_sdk::alt_bn128::prelude::ALT_BN128_ADDITION_OUTPUT_LEN;
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+use tracing::info;
+
+const BENCHMARK_NAME: &str = "Memory mapping";
+
+/// Name of the benchmark creating the memory mapping of a single syscall call
+pub const NEW_BENCHMARK_NAME: &str = "MemoryMapping::new";
+
+/// Name of the benchmark translating the input slice of a single syscall call
+pub const TRANSLATE_BENCHMARK_NAME: &str = "translate_slice";
+
+/// Memory mapping of all the inputs of a pool and of the syscall output, built once and
+/// reused by every call as the loader does for a program invocation
+pub struct PoolMapping<'a> {
+    memory_mapping: MemoryMapping<'a>,
+    /// VM address of the first input
+    input_addr: u64,
+    /// Distance between the VM addresses of the inputs in bytes
+    stride: u64,
+    /// Output region written by the syscalls, mapped at `OUTPUT_VM_ADDR`
+    output: Vec<u8>,
+    _pool: PhantomData<&'a InputPool>,
+}
+
+impl<'a> PoolMapping<'a> {
+    /// Maps the inputs of the pool one after another from the input VM address of the
+    /// alignment and an output of the given size. The inputs keep the misalignment of the
+    /// first one.
+    pub fn new(
+        pool: &'a InputPool,
+        context: &'a SyscallContext,
+        alignment: &Alignment,
+        output_len: usize,
+    ) -> Result<Self, Error> {
+        let input_addr = alignment.input_addr();
+        let align = align_of::<u64>() as u64;
+        let input_size = pool.input_size() as u64;
+        let stride = input_size + (align - input_size % align) % align;
+        let mut output = vec![0u8; output_len];
+
+        let regions = pool
+            .inputs()
+            .enumerate()
+            .map(|(index, input)| {
+                MemoryRegion::new_from_slice(input, input_addr + index as u64 * stride, 0, false)
+            })
+            .chain(std::iter::once(writable_region(
+                &mut output,
+                OUTPUT_VM_ADDR,
+            )))
+            .collect();
+        let memory_mapping =
+            MemoryMapping::new::<BpfError>(regions, &context.config).map_err(Error::Mapping)?;
+
+        Ok(Self {
+            memory_mapping,
+            input_addr,
+            stride,
+            output,
+            _pool: PhantomData,
+        })
+    }
+
+    /// Memory mapping passed to the syscalls
+    pub fn memory_mapping(&self) -> &MemoryMapping<'a> {
+        &self.memory_mapping
+    }
+
+    /// Output written by the last syscall call
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// VM address of the input at the given index of the pool
+    pub fn input_addr(&self, index: usize) -> u64 {
+        self.input_addr + index as u64 * self.stride
+    }
+}
+
+/// Continuous writable region of the given buffer mapped at the given VM address. Built from
+/// the mutable borrow, `MemoryRegion::new_from_slice` only takes a shared one.
+fn writable_region(buffer: &mut [u8], vm_addr: u64) -> MemoryRegion {
+    MemoryRegion {
+        host_addr: buffer.as_mut_ptr() as u64,
+        vm_addr,
+        len: buffer.len() as u64,
+        vm_gap_shift: size_of::<u64>() as u8 * 8 - 1,
+        is_writable: true,
+    }
+}
+
+/// Runs the benchmarks of the memory mapping overhead of an alt_bn128 Addition call on the inputs
+/// of the pool: creating the mapping of the input and the output, as the runners did for every
+/// call, and translating the input slice. Returns the measurement of both.
+pub fn mapping_bench(
+    pool: &InputPool,
+    harness: &Harness,
+    k: Option<f64>,
+    context: &SyscallContext,
+    alignment: &Alignment,
+) -> Result<Vec<(&'static str, Measurement)>, Error> {
+    info!("> Start {} benchmarks...", BENCHMARK_NAME);
+    let input_addr = alignment.input_addr();
+    let input_size = pool.input_size() as u64;
+    let mut output = vec![0u8; ALT_BN128_ADDITION_OUTPUT_LEN];
+
+    let new = measure_call(NEW_BENCHMARK_NAME, harness, k, |i| {
+        MemoryMapping::new::<BpfError>(
+            vec![
+                MemoryRegion::new_from_slice(pool.get(i), input_addr, 0, false),
+                writable_region(&mut output, OUTPUT_VM_ADDR),
+            ],
+            &context.config,
+        )
+        .map_err(Error::Mapping)?;
+        Ok(())
+    })?;
+
+    separator();
+    let mapping = PoolMapping::new(pool, context, alignment, output.len())?;
+    let translate = measure_call(TRANSLATE_BENCHMARK_NAME, harness, k, |i| {
+        translate_slice::<u8>(
+            mapping.memory_mapping(),
+            mapping.input_addr(pool.index(i)),
+            input_size,
+            &context.loader_id,
+            context.enforce_aligned_host_addrs,
+        )
+        .map_err(Error::Syscall)?;
+        Ok(())
+    })?;
+
+    Ok(vec![
+        (NEW_BENCHMARK_NAME, new),
+        (TRANSLATE_BENCHMARK_NAME, translate),
+    ])
+}
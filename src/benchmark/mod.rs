@@ -1,85 +1,1013 @@
 //! alt-bn128-bench benchmark module
 
+mod affinity;
+mod alignment;
 mod alt_bn128_addition;
+mod alt_bn128_compression;
 mod alt_bn128_multiplication;
 mod alt_bn128_pairing;
+mod alt_bn128_points;
+mod blake3;
+mod clock;
+mod consistency;
+mod counters;
+mod harness;
 mod keccak;
+mod mapping;
+mod pool;
+mod secp256k1_ecdsa;
+mod sha256;
+mod throughput;
 
-use crate::cli::Bench;
+use self::blake3::blake3_bench;
+use crate::alt_bn128::{BpfError, SyscallContext, SyscallError};
+use crate::alt_bn128_compression::{
+    ALT_BN128_G1_COMPRESS, ALT_BN128_G1_DECOMPRESS, ALT_BN128_G2_COMPRESS, ALT_BN128_G2_DECOMPRESS,
+};
+use crate::error::Error;
+use crate::host::Host;
+use crate::options::{Baseline, Bench, CacheMode, ClockKind};
+use crate::report::{BenchmarkResult, CounterAverages, Parameters, Report};
+use crate::significant;
+use affinity::{busy_siblings, pin, set_priority};
+use alignment::alignment_report;
 use alt_bn128_addition::alt_bn128_bench_addition;
+use alt_bn128_compression::{alt_bn128_bench_compression, CompressionInputs};
 use alt_bn128_multiplication::alt_bn128_bench_multiplication;
 use alt_bn128_pairing::alt_bn128_bench_pairing;
+use alt_bn128_points::{alt_bn128_points_bench, pairing_report};
+use consistency::consistency_report;
+use counters::Counters;
 use keccak::keccak_bench;
-use tracing::info;
+use mapping::mapping_bench;
+use secp256k1_ecdsa::secp256k1_bench;
+use sha256::sha256_bench;
+use solana_rbpf::error::EbpfError;
+use solana_sdk::instruction::InstructionError;
+use std::collections::BTreeMap;
+use throughput::{scaling_report, throughput_bench, Operation, Throughput};
+use tracing::{info, warn};
 
+pub use affinity::Priority;
+pub use alignment::Alignment;
+pub use clock::Clock;
+pub use harness::Harness;
+pub use harness::Measurement;
+pub use pool::{InputPool, PoolLimits};
+
+/// Number of significant digits of the reported numbers
 pub const PRECISION: usize = 4;
 
-/// Runs the benchmark
-pub fn run(count: usize, size: usize, bench: Option<Bench>) {
-    let buffers = generate_buffers(count, size);
+/// Virtual address of the memory region holding the syscall input
+pub const INPUT_VM_ADDR: u64 = 0;
+
+/// Virtual address of the memory region receiving the syscall output
+pub const OUTPUT_VM_ADDR: u64 = 1 << 32;
+
+/// Consistency check of the compute units derived from all the baselines
+pub struct Consistency {
+    /// Relative spread of the compute units flagged as inconsistent
+    pub tolerance: f64,
+}
+
+/// Source of the baseline the ratios are relative to
+pub enum BaselineSource {
+    /// Measures the given hash
+    Measure(Baseline),
+    /// Average elapsed time of the given hash taken from the report of a previous run
+    Saved(Baseline, Box<Report>),
+    /// No baseline, ratios are not reported
+    Skip,
+}
+
+/// Benchmark settings
+pub struct Settings {
+    /// Size of random input buffer of the baselines in bytes
+    pub size: usize,
+    /// Bounds of the inputs generated for every benchmark
+    pub pool: PoolLimits,
+    /// Measurement settings shared by all the benchmarks
+    pub harness: Harness,
+    /// Benchmark run alone, all of them if not set
+    pub bench: Option<Bench>,
+    /// Source of the baseline the ratios are relative to
+    pub baseline: BaselineSource,
+    /// Compute units available to every alt_bn128 benchmark, no metering if not set
+    pub budget: Option<u64>,
+    /// Consistency check across all the baselines, skipped if not set
+    pub consistency: Option<Consistency>,
+    /// Number of threads running every benchmark concurrently in throughput mode
+    pub threads: usize,
+    /// CPU the benchmark thread is pinned to, the following CPUs take the other threads
+    pub pin_cpu: Option<usize>,
+    /// Scheduling priority of the benchmark threads, unchanged if not set
+    pub priority: Option<Priority>,
+    /// Cluster configuration the alt_bn128 syscalls run with
+    pub context: SyscallContext,
+    /// Addresses the alt_bn128 inputs are mapped at
+    pub alignment: Alignment,
+}
+
+/// Runs the benchmark and returns the results, fails on the first error
+/// unless the harness keeps going
+pub fn run(settings: Settings) -> Result<Report, Error> {
+    let Settings {
+        size,
+        pool,
+        mut harness,
+        bench,
+        baseline,
+        budget,
+        consistency,
+        threads,
+        pin_cpu,
+        priority,
+        context,
+        alignment,
+    } = settings;
+    if harness.time.is_none() && harness.count == 0 {
+        return Err(Error::Input(
+            "Number of rounds has to be positive without a target time".to_owned(),
+        ));
+    }
+    if let (Some(_), Some(budget)) = (harness.time, budget) {
+        return Err(Error::Input(format!(
+            "Compute budget of {} units cannot be combined with a target time, \
+             the pilot run would spend it before the measured rounds",
+            budget
+        )));
+    }
+    let count = harness.count;
+    let pool = match harness.cache {
+        CacheMode::Hot => pool.hot(),
+        CacheMode::Cold => pool,
+    };
+    let host = Host::detect();
+    host_report(&host);
+
+    if harness.counters {
+        if let Err(e) = Counters::open() {
+            warn!("Hardware counters unavailable: {}, measuring time only", e);
+            harness.counters = false;
+        }
+    }
+    if let Some(priority) = priority {
+        match set_priority(priority) {
+            Ok(()) => info!("Running with {} priority", priority),
+            Err(e) => warn!("{}, running with the default priority", e),
+        }
+    }
+
+    let cpus: Vec<usize> = match pin_cpu {
+        Some(first) => (first..first + threads).collect(),
+        None => vec![],
+    };
+    for (cpu, sibling) in busy_siblings(&cpus) {
+        warn!(
+            "CPUs {} and {} are SMT siblings, their benchmark threads compete for the same core",
+            cpu, sibling
+        );
+    }
+
+    let mut report = Report {
+        host,
+        cpus,
+        results: vec![],
+    };
+
+    if bench == Some(Bench::Alignment) {
+        alignment_report(&context)?;
+        return Ok(report);
+    }
+    info!(
+        "alt_bn128 syscalls called by loader {}, enforce_aligned_host_addrs {}",
+        context.loader_id, context.enforce_aligned_host_addrs
+    );
+    if alignment.is_misaligned() {
+        info!("alt_bn128 inputs mapped at {}", alignment);
+    }
+
+    if threads > 1 {
+        if harness.clock.kind == ClockKind::Process {
+            warn!("Process clock sums up all the threads, measuring with wall clock instead");
+            harness.clock = Clock::new(ClockKind::Wall)?;
+        }
+        if budget.is_some() || consistency.is_some() {
+            warn!("Compute budget and consistency check are not applied in throughput mode");
+        }
+        report.results = throughput_run(
+            bench,
+            threads,
+            &report.cpus,
+            &pool,
+            size,
+            &harness,
+            &context,
+            &alignment,
+        )?;
+        return Ok(report);
+    }
+
+    if let Some(&cpu) = report.cpus.first() {
+        match pin(cpu) {
+            Ok(()) => info!("Pinned to CPU {}", cpu),
+            Err(e) => {
+                warn!("{}, running unpinned", e);
+                report.cpus.clear();
+            }
+        }
+    }
+
+    info!(
+        "Measuring with cache {}, clock {} (resolution {} s.)",
+        harness.cache,
+        harness.clock.kind,
+        significant::precision(harness.clock.resolution().as_secs_f64(), PRECISION)
+    );
+
+    let (selected, mut baselines) = match baseline {
+        BaselineSource::Measure(baseline) => (Some(baseline), vec![]),
+        BaselineSource::Saved(baseline, saved) => {
+            let k = saved_baseline(&saved, baseline, size, &harness, &report.host)?;
+            info!(
+                "{} baseline average: {} s. (saved report of {})",
+                baseline,
+                significant::precision(k, PRECISION),
+                saved.host.name
+            );
+            (Some(baseline), vec![(baseline, k)])
+        }
+        BaselineSource::Skip => (None, vec![]),
+    };
 
-    println!();
-    let k = keccak_bench(&buffers);
+    let measured: Vec<Baseline> = match selected {
+        Some(_) if consistency.is_some() => Baseline::ALL.to_vec(),
+        Some(selected) => vec![selected],
+        None => vec![],
+    }
+    .into_iter()
+    .filter(|baseline| baselines.iter().all(|(b, _)| b != baseline))
+    .collect();
+
+    if !measured.is_empty() {
+        let buffers = generate_buffers(&pool, count, size);
+        for baseline in measured {
+            separator();
+            let measurement = baseline_bench(baseline, &buffers, &harness)?;
+            baselines.push((baseline, measurement.average()));
+            report.results.push(result(
+                &baseline.to_string(),
+                size,
+                &harness,
+                measurement,
+                None,
+            ));
+        }
+    }
+
+    let k = selected
+        .map(|selected| {
+            baselines
+                .iter()
+                .find(|(b, _)| *b == selected)
+                .map(|(_, k)| *k)
+                .ok_or_else(|| Error::Input(format!("{} baseline was not measured", selected)))
+        })
+        .transpose()?;
+    let reference = selected.map(|selected| selected.to_string()).zip(k);
+    match selected {
+        Some(selected) => info!("K-ratios are relative to the {} baseline", selected),
+        None => info!("No baseline, K-ratios are not reported"),
+    }
+
+    if bench == Some(Bench::Keccak) {
+        return Ok(report);
+    }
+
+    let mut operations = Vec::new();
 
     if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Addition) {
-        println!();
-        let addition_inputs = generate_buffers_add(count);
-        alt_bn128_bench_addition(&addition_inputs, k);
+        separator();
+        let addition_inputs = generate_buffers_add(&pool, count)?.misalign(alignment.host_offset);
+        let measurement =
+            alt_bn128_bench_addition(&addition_inputs, &harness, k, budget, &context, &alignment)?;
+        operations.push((alt_bn128_addition::BENCHMARK_NAME, measurement.average()));
+        report.results.push(result(
+            alt_bn128_addition::BENCHMARK_NAME,
+            addition_inputs.input_size(),
+            &harness,
+            measurement,
+            reference.clone(),
+        ));
     }
 
     if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Multiplication) {
-        println!();
-        let multiplication_inputs = generate_buffers_mul(count);
-        alt_bn128_bench_multiplication(&multiplication_inputs, k);
+        separator();
+        let multiplication_inputs =
+            generate_buffers_mul(&pool, count)?.misalign(alignment.host_offset);
+        let measurement = alt_bn128_bench_multiplication(
+            &multiplication_inputs,
+            &harness,
+            k,
+            budget,
+            &context,
+            &alignment,
+        )?;
+        operations.push((
+            alt_bn128_multiplication::BENCHMARK_NAME,
+            measurement.average(),
+        ));
+        report.results.push(result(
+            alt_bn128_multiplication::BENCHMARK_NAME,
+            multiplication_inputs.input_size(),
+            &harness,
+            measurement,
+            reference.clone(),
+        ));
     }
 
     if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Pairing) {
+        separator();
+        let pairing_inputs = generate_buffers_pair(&pool, count)?.misalign(alignment.host_offset);
+        let measurement =
+            alt_bn128_bench_pairing(&pairing_inputs, &harness, k, budget, &context, &alignment)?;
+        operations.push((alt_bn128_pairing::BENCHMARK_NAME, measurement.average()));
+        report.results.push(result(
+            alt_bn128_pairing::BENCHMARK_NAME,
+            pairing_inputs.input_size(),
+            &harness,
+            measurement,
+            reference.clone(),
+        ));
+    }
+
+    let mut compression_ops = Vec::new();
+    if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Compression) {
+        compression_ops.extend(&[ALT_BN128_G1_COMPRESS, ALT_BN128_G2_COMPRESS]);
+    }
+    if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Decompression) {
+        compression_ops.extend(&[ALT_BN128_G1_DECOMPRESS, ALT_BN128_G2_DECOMPRESS]);
+    }
+    for op in compression_ops {
+        separator();
+        let compression_inputs =
+            CompressionInputs::generate(&pool, count, op)?.misalign(alignment.host_offset);
+        let measurement = alt_bn128_bench_compression(
+            &compression_inputs,
+            &harness,
+            k,
+            budget,
+            &context,
+            &alignment,
+        )?;
+        operations.push((compression_inputs.name(), measurement.average()));
+        report.results.push(result(
+            compression_inputs.name(),
+            compression_inputs.input_size(),
+            &harness,
+            measurement,
+            reference.clone(),
+        ));
+    }
+
+    if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Points) {
+        separator();
+        let measurements = alt_bn128_points_bench(&harness, k)?;
+        let pairing = operations
+            .iter()
+            .find(|(name, _)| *name == alt_bn128_pairing::BENCHMARK_NAME)
+            .map(|(_, average)| *average);
+        pairing_report(&measurements, pairing);
+        for (name, input_size, measurement) in measurements {
+            report.results.push(result(
+                name,
+                input_size,
+                &harness,
+                measurement,
+                reference.clone(),
+            ));
+        }
+    }
+
+    if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Mapping) {
+        separator();
+        let mapping_inputs = generate_buffers_add(&pool, count)?.misalign(alignment.host_offset);
+        for (name, measurement) in
+            mapping_bench(&mapping_inputs, &harness, k, &context, &alignment)?
+        {
+            report.results.push(result(
+                name,
+                mapping_inputs.input_size(),
+                &harness,
+                measurement,
+                reference.clone(),
+            ));
+        }
+    }
+
+    if bench.is_none() || bench.as_ref().unwrap().eq(&Bench::Secp256k1) {
+        separator();
+        for (name, measurement) in secp256k1_bench(&harness, k)? {
+            report.results.push(result(
+                name,
+                secp256k1_ecdsa::MESSAGE_SIZE,
+                &harness,
+                measurement,
+                reference.clone(),
+            ));
+        }
+    }
+
+    if let Some(consistency) = consistency {
+        separator();
+        if baselines.is_empty() {
+            warn!("Consistency check needs baselines, skipped");
+        } else {
+            consistency_report(&operations, &baselines, size, consistency.tolerance);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Runs the selected operations on one and on the given number of threads,
+/// returns the results of both
+#[allow(clippy::too_many_arguments)]
+fn throughput_run(
+    bench: Option<Bench>,
+    threads: usize,
+    cpus: &[usize],
+    pool: &PoolLimits,
+    size: usize,
+    harness: &Harness,
+    context: &SyscallContext,
+    alignment: &Alignment,
+) -> Result<Vec<BenchmarkResult>, Error> {
+    let operations = match bench {
+        None => vec![
+            Operation::Keccak,
+            Operation::Addition,
+            Operation::Multiplication,
+            Operation::Pairing,
+        ],
+        Some(Bench::Keccak) => vec![Operation::Keccak],
+        Some(Bench::Addition) => vec![Operation::Addition],
+        Some(Bench::Multiplication) => vec![Operation::Multiplication],
+        Some(Bench::Pairing) => vec![Operation::Pairing],
+        Some(Bench::Secp256k1) => {
+            warn!("secp256k1 benchmarks do not support throughput mode");
+            vec![]
+        }
+        Some(Bench::Compression) | Some(Bench::Decompression) => {
+            warn!("alt_bn128 compression benchmarks do not support throughput mode");
+            vec![]
+        }
+        Some(Bench::Points) => {
+            warn!("alt_bn128 points benchmarks do not support throughput mode");
+            vec![]
+        }
+        Some(Bench::Mapping) => {
+            warn!("Memory mapping benchmarks do not support throughput mode");
+            vec![]
+        }
+        Some(Bench::Alignment) => vec![],
+    };
+
+    let mut results = Vec::new();
+    for operation in operations {
+        separator();
+        let single = throughput_bench(operation, 1, cpus, pool, size, harness, context, alignment)?;
+        let multi = throughput_bench(
+            operation, threads, cpus, pool, size, harness, context, alignment,
+        )?;
+        scaling_report(operation, &single, &multi);
+        results.push(throughput_result(operation, harness, single));
+        results.push(throughput_result(operation, harness, multi));
+    }
+    Ok(results)
+}
+
+/// Logs the machine and warns about the settings distorting the measurements
+fn host_report(host: &Host) {
+    info!("Host {}", host);
+    match host.governor.as_deref() {
+        Some("performance") | None => {}
+        Some(governor) => warn!(
+            "CPU frequency governor is {}, not performance, the timings are not stable",
+            governor
+        ),
+    }
+    if host.turbo == Some(true) {
+        warn!("Turbo boost is enabled, the timings depend on the temperature and the load");
+    }
+}
+
+/// Runs the benchmark of the given baseline hash and returns the measurement
+fn baseline_bench(
+    baseline: Baseline,
+    buffers: &InputPool,
+    harness: &Harness,
+) -> Result<Measurement, Error> {
+    match baseline {
+        Baseline::Keccak => keccak_bench(buffers, harness),
+        Baseline::Sha256 => sha256_bench(buffers, harness),
+        Baseline::Blake3 => blake3_bench(buffers, harness),
+    }
+}
+
+/// Parameters of a single-threaded result on inputs of the given size
+fn parameters(input_size: usize, harness: &Harness) -> Parameters {
+    Parameters {
+        input_size,
+        cache: harness.cache.to_string(),
+        clock: harness.clock.kind.to_string(),
+        clock_resolution: harness.clock.resolution().as_secs_f64(),
+        threads: 1,
+    }
+}
+
+/// Average of the given baseline hash in a previous report, which has to be measured
+/// on this machine with the same input size, cache mode and clock
+fn saved_baseline(
+    saved: &Report,
+    baseline: Baseline,
+    size: usize,
+    harness: &Harness,
+    host: &Host,
+) -> Result<f64, Error> {
+    if !saved.host.name.is_empty()
+        && (saved.host.name != host.name || saved.host.cpu_model != host.cpu_model)
+    {
+        return Err(Error::Input(format!(
+            "Saved baseline comes from a different machine, {} ({})",
+            saved.host.name, saved.host.cpu_model
+        )));
+    }
+    let parameters = parameters(size, harness);
+    match saved.find(&baseline.to_string(), &parameters) {
+        Some(result) => Ok(result.average),
+        None => {
+            let hashes: Vec<String> = saved
+                .results
+                .iter()
+                .filter(|result| result.name.parse::<Baseline>().is_ok())
+                .map(|result| format!("{} ({})", result.name, result.parameters))
+                .collect();
+            Err(Error::Input(format!(
+                "Saved report has no {} baseline with {}, it has [{}]",
+                baseline,
+                parameters,
+                hashes.join(", ")
+            )))
+        }
+    }
+}
+
+/// Result of the named benchmark for the report, relative to the baseline name and average
+fn result(
+    name: &str,
+    input_size: usize,
+    harness: &Harness,
+    measurement: Measurement,
+    reference: Option<(String, f64)>,
+) -> BenchmarkResult {
+    let parameters = parameters(input_size, harness);
+    let average = measurement.average();
+    let mut result = BenchmarkResult::new(
+        name,
+        parameters,
+        measurement.executed,
+        average,
+        measurement.samples,
+    )
+    .relative_to(reference);
+    result.failures = measurement.failures;
+    result.errors = measurement.errors;
+    result.counters = measurement.counters;
+    result
+}
+
+/// Result of the operation measured on many threads for the report,
+/// the samples, failures and error counts of all the threads are put together,
+/// the hardware events are averaged over the rounds of all the threads
+fn throughput_result(
+    operation: Operation,
+    harness: &Harness,
+    throughput: Throughput,
+) -> BenchmarkResult {
+    let parameters = Parameters {
+        threads: throughput.threads,
+        ..parameters(throughput.input_size, harness)
+    };
+    let rounds = throughput
+        .measurements
+        .iter()
+        .map(|measurement| measurement.executed)
+        .sum();
+    let latency = throughput.latency();
+    let counters = throughput
+        .measurements
+        .iter()
+        .map(|measurement| {
+            measurement
+                .counters
+                .map(|averages| (averages, measurement.executed as f64))
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|threads| {
+            let total = |value: fn(&CounterAverages) -> f64| {
+                threads
+                    .iter()
+                    .map(|(averages, executed)| value(averages) * executed)
+                    .sum::<f64>()
+                    / (rounds as f64).max(1.)
+            };
+            CounterAverages {
+                cycles: total(|averages| averages.cycles),
+                instructions: total(|averages| averages.instructions),
+                branch_misses: total(|averages| averages.branch_misses),
+                cache_misses: total(|averages| averages.cache_misses),
+            }
+        });
+    let mut samples = Vec::new();
+    let mut failures = Vec::new();
+    let mut errors = BTreeMap::new();
+    for measurement in throughput.measurements {
+        samples.extend(measurement.samples);
+        failures.extend(measurement.failures);
+        for (error, rounds) in measurement.errors {
+            *errors.entry(error).or_default() += rounds;
+        }
+    }
+    let mut result = BenchmarkResult::new(operation.name(), parameters, rounds, latency, samples);
+    result.failures = failures;
+    result.errors = errors;
+    result.counters = counters;
+    result
+}
+
+/// Separates the benchmarks in the console output, prints nothing without a logger
+pub fn separator() {
+    if tracing::dispatcher::has_been_set() {
         println!();
-        let pairing_inputs = generate_buffers_pair(count);
-        alt_bn128_bench_pairing(&pairing_inputs, k);
     }
 }
 
-/// Generates random data
-fn generate_buffers(count: usize, message_size: usize) -> Vec<Vec<u8>> {
+/// Measures the given call on the round, logs the average relative to the baseline if any
+/// and returns the measurement
+fn measure_call<F: FnMut(usize) -> Result<(), Error>>(
+    name: &str,
+    harness: &Harness,
+    k: Option<f64>,
+    mut f: F,
+) -> Result<Measurement, Error> {
+    info!("> Start {} benchmark...", name);
+
+    let measurement = harness.measure(|i| {
+        f(i)?;
+        Ok(true)
+    })?;
+
+    measurement_report(name, harness, k, &measurement);
+    Ok(measurement)
+}
+
+/// Measures the given syscall on the round like `measure_call`, stops at the first call
+/// exceeding the compute budget and records the failed rounds on the pool input `index` gives
+/// for the round, fails if the budget does not cover a single call
+fn measure_syscall<I, F>(
+    name: &str,
+    harness: &Harness,
+    k: Option<f64>,
+    index: I,
+    mut f: F,
+) -> Result<Measurement, Error>
+where
+    I: Fn(usize) -> usize,
+    F: FnMut(usize) -> Result<(), Error>,
+{
+    info!("> Start {} benchmark...", name);
+
+    let mut exceeded = false;
+    let measurement = harness.measure_recording(|i, failures| match f(i) {
+        Ok(()) => Ok(true),
+        Err(err) if budget_exceeded(&err) => {
+            exceeded = true;
+            Ok(false)
+        }
+        Err(err) => failures.record(harness, index(i), err),
+    })?;
+    if exceeded && measurement.executed == 0 {
+        return Err(Error::Input(format!(
+            "{} exceeded the compute budget on the first call, the budget is below its cost",
+            name
+        )));
+    }
+    if exceeded {
+        info!(
+            "{} compute budget exceeded after {} calls",
+            name, measurement.executed
+        );
+    }
+
+    measurement_report(name, harness, k, &measurement);
+    Ok(measurement)
+}
+
+/// Logs the elapsed time, the average relative to the baseline if any and the failed rounds
+/// of the named measurement
+fn measurement_report(name: &str, harness: &Harness, k: Option<f64>, measurement: &Measurement) {
+    let total = measurement.total;
+    let n = measurement.executed as f64;
+    let average = measurement.average();
+
+    info!("Finish {}", name);
     info!(
-        "Preparing {} buffers of {} bytes each...",
-        count, message_size
+        "{} ({} executions) elapsed {} s.",
+        name,
+        n,
+        significant::precision(total, PRECISION)
     );
+    match k {
+        Some(k) => info!(
+            "{} average: {} s. = {} K ({})",
+            name,
+            significant::precision(average, PRECISION),
+            significant::precision(average / k, PRECISION),
+            harness
+        ),
+        None => info!(
+            "{} average: {} s. ({})",
+            name,
+            significant::precision(average, PRECISION),
+            harness
+        ),
+    }
 
-    let mut buffers: Vec<Vec<u8>> = Vec::with_capacity(count);
-    for _ in 0..count {
-        buffers.push((0..message_size).map(|_| rand::random::<u8>()).collect());
+    if !measurement.failures.is_empty() {
+        warn!(
+            "{} failed on {} inputs, first: {}",
+            name,
+            measurement.failures.len(),
+            measurement.failures[0].error
+        );
+    }
+    for (error, rounds) in &measurement.errors {
+        warn!("{} returned {} in {} rounds", name, error, rounds);
     }
-    buffers
 }
 
-fn generate_buffers_add(count: usize) -> Vec<Vec<u8>> {
-    let input_pattern = "18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f3726607c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7";
-    let input = array_bytes::hex2bytes_unchecked(input_pattern);
-    let mut buffers: Vec<Vec<u8>> = Vec::with_capacity(count);
-    for _ in 0..count {
-        buffers.push(input.clone());
-    }
-    buffers
+/// Checks whether a syscall failed on its compute meter, other errors of the call
+/// draining the meter are not a budget stop
+fn budget_exceeded(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Syscall(EbpfError::UserError(BpfError::SyscallError(
+            SyscallError::InstructionError(InstructionError::ComputationalBudgetExceeded)
+        )))
+    )
 }
 
-fn generate_buffers_mul(count: usize) -> Vec<Vec<u8>> {
-    let input_pattern = "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb721611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb20400000000000000000000000000000000000000000000000011138ce750fa15c2";
-    let input = array_bytes::hex2bytes_unchecked(input_pattern);
-    let mut buffers: Vec<Vec<u8>> = Vec::with_capacity(count);
-    for _ in 0..count {
-        buffers.push(input.clone());
-    }
-    buffers
+/// Generates random data
+pub fn generate_buffers(limits: &PoolLimits, count: usize, message_size: usize) -> InputPool {
+    InputPool::generate(limits, count, message_size, || {
+        (0..message_size).map(|_| rand::random::<u8>()).collect()
+    })
+}
+
+/// Valid input of the alt_bn128 Addition in hex
+const ADDITION_INPUT: &str = "18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f3726607c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7";
+
+/// Valid input of the alt_bn128 Multiplication in hex
+const MULTIPLICATION_INPUT: &str = "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb721611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb20400000000000000000000000000000000000000000000000011138ce750fa15c2";
+
+/// Valid input of the alt_bn128 Pairing in hex
+const PAIRING_INPUT: &str = "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f593034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf704bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a416782bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+
+/// Generates the valid input of the alt_bn128 Addition
+pub fn generate_buffers_add(limits: &PoolLimits, count: usize) -> Result<InputPool, Error> {
+    let input = parse_hex(ADDITION_INPUT)?;
+    Ok(InputPool::generate(limits, count, input.len(), || {
+        input.clone()
+    }))
+}
+
+/// Generates the valid input of the alt_bn128 Multiplication
+pub fn generate_buffers_mul(limits: &PoolLimits, count: usize) -> Result<InputPool, Error> {
+    let input = parse_hex(MULTIPLICATION_INPUT)?;
+    Ok(InputPool::generate(limits, count, input.len(), || {
+        input.clone()
+    }))
+}
+
+/// Generates the valid input of the alt_bn128 Pairing
+pub fn generate_buffers_pair(limits: &PoolLimits, count: usize) -> Result<InputPool, Error> {
+    let input = parse_hex(PAIRING_INPUT)?;
+    Ok(InputPool::generate(limits, count, input.len(), || {
+        input.clone()
+    }))
+}
+
+/// Decodes the hex input pattern
+fn parse_hex(pattern: &str) -> Result<Vec<u8>, Error> {
+    array_bytes::hex2bytes(pattern).map_err(|e| Error::Input(format!("{:?}", e)))
 }
 
-fn generate_buffers_pair(count: usize) -> Vec<Vec<u8>> {
-    let input_pattern = "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f593034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf704bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a416782bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
-    let input = array_bytes::hex2bytes_unchecked(input_pattern);
-    let mut buffers: Vec<Vec<u8>> = Vec::with_capacity(count);
-    for _ in 0..count {
-        buffers.push(input.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::alt_bn128::prelude::AltBn128Error;
+    use std::time::Duration;
+
+    fn harness(keep_going: bool) -> Harness {
+        Harness {
+            count: 10,
+            time: None,
+            precision: None,
+            cache: CacheMode::Hot,
+            scratch_size: 0,
+            clock: Clock::new(ClockKind::Wall).unwrap(),
+            counters: false,
+            keep_going,
+        }
+    }
+
+    const LIMITS: PoolLimits = PoolLimits {
+        inputs: 1,
+        bytes: 1 << 16,
+    };
+
+    fn budget_error() -> Error {
+        Error::Syscall(EbpfError::UserError(BpfError::SyscallError(
+            SyscallError::InstructionError(InstructionError::ComputationalBudgetExceeded),
+        )))
+    }
+
+    #[test]
+    fn failed_syscall_rounds_are_recorded_on_their_inputs() {
+        let harness = harness(true);
+        let measurement = measure_syscall(
+            "test",
+            &harness,
+            None,
+            |i| i % 3,
+            |i| match i % 2 {
+                0 => Ok(()),
+                _ => Err(Error::AltBn128(AltBn128Error::InvalidInputData)),
+            },
+        )
+        .unwrap();
+        assert_eq!(measurement.executed, 10);
+        let failures: Vec<(usize, usize)> = measurement
+            .failures
+            .iter()
+            .map(|failure| (failure.input, failure.rounds))
+            .collect();
+        assert_eq!(failures, vec![(0, 2), (1, 2), (2, 1)]);
+        assert_eq!(measurement.errors.get("InvalidInputData"), Some(&5));
+    }
+
+    #[test]
+    fn syscall_rounds_stop_at_the_compute_budget() {
+        let harness = harness(false);
+        let measurement = measure_syscall(
+            "test",
+            &harness,
+            None,
+            |i| i,
+            |i| if i < 3 { Ok(()) } else { Err(budget_error()) },
+        )
+        .unwrap();
+        assert_eq!(measurement.executed, 3);
+        assert!(measurement.failures.is_empty());
+    }
+
+    #[test]
+    fn compute_budget_below_a_single_call_is_an_error() {
+        let context = SyscallContext::default();
+        let pool = generate_buffers_add(&LIMITS, 1).unwrap();
+        let result = alt_bn128_bench_addition(
+            &pool,
+            &harness(false),
+            None,
+            Some(context.costs.addition - 1),
+            &context,
+            &Alignment::default(),
+        );
+        assert!(matches!(result, Err(Error::Input(_))));
+    }
+
+    #[test]
+    fn compute_budget_stops_after_the_affordable_calls() {
+        let context = SyscallContext::default();
+        let alignment = Alignment::default();
+        let harness = harness(false);
+        let budget = |cost: u64| Some(3 * cost + cost / 2);
+
+        let addition = generate_buffers_add(&LIMITS, 1).unwrap();
+        let multiplication = generate_buffers_mul(&LIMITS, 1).unwrap();
+        let pairing = generate_buffers_pair(&LIMITS, 1).unwrap();
+        let mut syscalls = vec![
+            (
+                alt_bn128_addition::BENCHMARK_NAME,
+                alt_bn128_bench_addition(
+                    &addition,
+                    &harness,
+                    None,
+                    budget(context.costs.addition),
+                    &context,
+                    &alignment,
+                ),
+            ),
+            (
+                alt_bn128_multiplication::BENCHMARK_NAME,
+                alt_bn128_bench_multiplication(
+                    &multiplication,
+                    &harness,
+                    None,
+                    budget(context.costs.multiplication),
+                    &context,
+                    &alignment,
+                ),
+            ),
+            (
+                alt_bn128_pairing::BENCHMARK_NAME,
+                alt_bn128_bench_pairing(
+                    &pairing,
+                    &harness,
+                    None,
+                    budget(context.costs.pairing(pairing.input_size() as u64)),
+                    &context,
+                    &alignment,
+                ),
+            ),
+        ];
+        for &op in &[
+            ALT_BN128_G1_COMPRESS,
+            ALT_BN128_G1_DECOMPRESS,
+            ALT_BN128_G2_COMPRESS,
+            ALT_BN128_G2_DECOMPRESS,
+        ] {
+            let inputs = CompressionInputs::generate(&LIMITS, 1, op).unwrap();
+            syscalls.push((
+                inputs.name(),
+                alt_bn128_bench_compression(
+                    &inputs,
+                    &harness,
+                    None,
+                    budget(context.costs.compression(op).unwrap()),
+                    &context,
+                    &alignment,
+                ),
+            ));
+        }
+
+        for (name, measurement) in syscalls {
+            assert_eq!(measurement.unwrap().executed, 3, "{}", name);
+        }
+    }
+
+    #[test]
+    fn compute_budget_is_refused_with_a_target_time() {
+        let settings = Settings {
+            size: 100,
+            pool: LIMITS,
+            harness: Harness {
+                time: Some(Duration::from_millis(20)),
+                ..harness(false)
+            },
+            bench: Some(Bench::Addition),
+            baseline: BaselineSource::Skip,
+            budget: Some(1000),
+            consistency: None,
+            threads: 1,
+            pin_cpu: None,
+            priority: None,
+            context: SyscallContext::default(),
+            alignment: Alignment::default(),
+        };
+        assert!(matches!(run(settings), Err(Error::Input(_))));
+    }
+
+    #[test]
+    fn zero_rounds_are_refused() {
+        let settings = Settings {
+            size: 100,
+            pool: LIMITS,
+            harness: Harness {
+                count: 0,
+                ..harness(false)
+            },
+            bench: Some(Bench::Addition),
+            baseline: BaselineSource::Skip,
+            budget: None,
+            consistency: None,
+            threads: 1,
+            pin_cpu: None,
+            priority: None,
+            context: SyscallContext::default(),
+            alignment: Alignment::default(),
+        };
+        assert!(matches!(run(settings), Err(Error::Input(_))));
     }
-    buffers
 }
@@ -0,0 +1,101 @@
+use tracing::info;
+
+/// Bounds of an input pool
+#[derive(Clone, Copy, Debug)]
+pub struct PoolLimits {
+    /// Maximum number of inputs
+    pub inputs: usize,
+    /// Maximum memory used by the inputs in bytes
+    pub bytes: usize,
+}
+
+/// Memory of the inputs reused in cache-hot mode, fits into the L1 data cache
+const HOT_POOL_MEMORY: usize = 32 * 1024;
+
+impl PoolLimits {
+    /// Limits of a small working set staying in the CPU caches
+    pub fn hot(&self) -> Self {
+        Self {
+            inputs: self.inputs,
+            bytes: self.bytes.min(HOT_POOL_MEMORY),
+        }
+    }
+
+    /// Number of inputs of the given size to generate for `count` rounds
+    pub fn len(&self, count: usize, input_size: usize) -> usize {
+        count
+            .min(self.inputs)
+            .min(self.bytes / input_size.max(1))
+            .max(1)
+    }
+}
+
+/// Bounded set of inputs generated once and cycled through by a benchmark,
+/// so that memory use does not depend on the number of rounds.
+pub struct InputPool {
+    inputs: Vec<Vec<u8>>,
+    /// Bytes in front of every input misaligning its host address
+    padding: usize,
+}
+
+impl InputPool {
+    /// Generates the inputs of the given size within the limits
+    pub fn generate<F>(limits: &PoolLimits, count: usize, input_size: usize, generator: F) -> Self
+    where
+        F: FnMut() -> Vec<u8>,
+    {
+        let len = limits.len(count, input_size);
+        info!(
+            "Preparing pool of {} buffers of {} bytes each ({} bytes)...",
+            len,
+            input_size,
+            len * input_size
+        );
+
+        Self {
+            inputs: std::iter::repeat_with(generator).take(len).collect(),
+            padding: 0,
+        }
+    }
+
+    /// Moves every input the given number of bytes past the aligned start of its buffer
+    pub fn misalign(self, offset: usize) -> Self {
+        if offset == self.padding {
+            return self;
+        }
+        let padding = self.padding;
+        let inputs = self
+            .inputs
+            .into_iter()
+            .map(|input| {
+                let mut padded = vec![0u8; offset];
+                padded.extend_from_slice(&input[padding..]);
+                padded
+            })
+            .collect();
+        Self {
+            inputs,
+            padding: offset,
+        }
+    }
+
+    /// Size of a single input in bytes
+    pub fn input_size(&self) -> usize {
+        self.inputs[0].len() - self.padding
+    }
+
+    /// Input of the given round cycling through the pool
+    pub fn get(&self, round: usize) -> &[u8] {
+        &self.inputs[self.index(round)][self.padding..]
+    }
+
+    /// All the inputs of the pool
+    pub fn inputs(&self) -> impl Iterator<Item = &[u8]> {
+        self.inputs.iter().map(move |input| &input[self.padding..])
+    }
+
+    /// Index in the pool of the input of the given round
+    pub fn index(&self, round: usize) -> usize {
+        round % self.inputs.len()
+    }
+}
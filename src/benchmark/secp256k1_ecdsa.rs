@@ -0,0 +1,336 @@
+use super::harness::{Harness, Measurement};
+use super::{measurement_report, separator, PRECISION};
+use crate::error::Error;
+use crate::significant;
+use ecdsa::hazmat::VerifyPrimitive;
+use secp256k1::{Secp256k1, VerifyOnly};
+use solana_sdk::keccak;
+use std::convert::TryFrom;
+use std::fmt;
+use tracing::info;
+
+const BENCHMARK_NAME: &str = "secp256k1";
+
+/// Size of the signed random message in bytes
+pub const MESSAGE_SIZE: usize = 128;
+
+/// Signed message shared by all the secp256k1 implementations
+struct SignedMessage {
+    digest: [u8; 32],
+    signature: [u8; 64],
+    recovery_id: u8,
+    public_key: [u8; 33],
+}
+
+/// Single public key recovery, returns the compressed public key
+type Recover = fn(&Secp256k1<VerifyOnly>, &SignedMessage) -> Result<[u8; 33], Error>;
+
+/// Single signature verification
+type Verify = fn(&Secp256k1<VerifyOnly>, &SignedMessage) -> Result<bool, Error>;
+
+const RECOVERS: [(&str, Recover); 3] = [
+    ("k256 recover", k256_recover),
+    ("libsecp256k1 recover", libsecp256k1_recover),
+    ("secp256k1 recover", secp256k1_recover),
+];
+
+const VERIFIES: [(&str, Verify); 3] = [
+    ("k256 verify", k256_verify),
+    ("libsecp256k1 verify", libsecp256k1_verify),
+    ("secp256k1 verify", secp256k1_verify),
+];
+
+/// Runs the secp256k1 recover and verify benchmarks of all the implementations,
+/// returns the measurement of every implementation
+pub fn secp256k1_bench(
+    harness: &Harness,
+    k: Option<f64>,
+) -> Result<Vec<(&'static str, Measurement)>, Error> {
+    info!("> Start {} benchmark...", BENCHMARK_NAME);
+    // The secp256k1 context is expensive to create and is reused by every call
+    let secp = Secp256k1::verification_only();
+    let input = generate_signed_message()?;
+    validate(&secp, &input)?;
+
+    let recovers: Vec<(&str, Measurement)> = RECOVERS
+        .iter()
+        .map(|(name, recover)| {
+            separator();
+            let measurement =
+                secp256k1_measure(name, harness, k, || recover(&secp, &input).map(drop))?;
+            Ok((*name, measurement))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let verifies: Vec<(&str, Measurement)> = VERIFIES
+        .iter()
+        .map(|(name, verify)| {
+            separator();
+            let measurement =
+                secp256k1_measure(name, harness, k, || verified(name, *verify, &secp, &input))?;
+            Ok((*name, measurement))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    separator();
+    info!("Finish {}", BENCHMARK_NAME);
+    report_relative_speed(&recovers);
+    report_relative_speed(&verifies);
+
+    Ok(recovers.into_iter().chain(verifies).collect())
+}
+
+/// Generates a random message signed by a random key
+fn generate_signed_message() -> Result<SignedMessage, Error> {
+    use secp256k1::{Message, PublicKey, SecretKey};
+
+    let message: Vec<u8> = (0..MESSAGE_SIZE).map(|_| rand::random::<u8>()).collect();
+    let digest = keccak::hash(&message).to_bytes();
+
+    let secp = Secp256k1::new();
+    let secret_key = loop {
+        if let Ok(secret_key) = SecretKey::from_slice(&rand::random::<[u8; 32]>()) {
+            break secret_key;
+        }
+    };
+    let message = Message::from_slice(&digest).map_err(invalid("secp256k1 message"))?;
+    let (recovery_id, signature) = secp
+        .sign_recoverable(&message, &secret_key)
+        .serialize_compact();
+
+    Ok(SignedMessage {
+        digest,
+        signature,
+        recovery_id: recovery_id.to_i32() as u8,
+        public_key: PublicKey::from_secret_key(&secp, &secret_key).serialize(),
+    })
+}
+
+/// Checks that every implementation recovers the signer and accepts the signature
+fn validate(secp: &Secp256k1<VerifyOnly>, input: &SignedMessage) -> Result<(), Error> {
+    for (name, recover) in RECOVERS.iter() {
+        let public_key = recover(secp, input)?;
+        if public_key != input.public_key {
+            return Err(Error::Output(format!(
+                "{} recovered the public key {}, expected {}",
+                name,
+                array_bytes::bytes2hex("", &public_key[..]),
+                array_bytes::bytes2hex("", &input.public_key[..])
+            )));
+        }
+    }
+    for (name, verify) in VERIFIES.iter() {
+        if !verify(secp, input)? {
+            return Err(rejected(name));
+        }
+    }
+    Ok(())
+}
+
+/// Runs the signature verification, a rejected signature is an error
+fn verified(
+    name: &str,
+    verify: Verify,
+    secp: &Secp256k1<VerifyOnly>,
+    input: &SignedMessage,
+) -> Result<(), Error> {
+    match verify(secp, input)? {
+        true => Ok(()),
+        false => Err(rejected(name)),
+    }
+}
+
+/// Error of an implementation rejecting the valid signature
+fn rejected(name: &str) -> Error {
+    Error::Output(format!("{} rejected a valid signature", name))
+}
+
+/// Maps an error of an implementation parsing the signed message to an input error
+fn invalid<E: fmt::Debug>(what: &'static str) -> impl Fn(E) -> Error {
+    move |e| Error::Input(format!("Invalid {}: {:?}", what, e))
+}
+
+/// Maps an error of an implementation recovering the public key to an output error
+fn failed<E: fmt::Debug>(what: &'static str) -> impl Fn(E) -> Error {
+    move |e| Error::Output(format!("{} failed: {:?}", what, e))
+}
+
+/// Measures the given secp256k1 call on the single signed message, records the failed rounds
+/// on it and returns the measurement
+fn secp256k1_measure<F: FnMut() -> Result<(), Error>>(
+    name: &str,
+    harness: &Harness,
+    k: Option<f64>,
+    mut f: F,
+) -> Result<Measurement, Error> {
+    info!("> Start {} benchmark...", name);
+
+    let measurement = harness.measure_recording(|_, failures| match f() {
+        Ok(()) => Ok(true),
+        Err(err) => failures.record(harness, 0, err),
+    })?;
+
+    measurement_report(name, harness, k, &measurement);
+    Ok(measurement)
+}
+
+/// Logs the speed of every implementation relative to the fastest one
+fn report_relative_speed(measurements: &[(&str, Measurement)]) {
+    let fastest = measurements
+        .iter()
+        .map(|(_, measurement)| measurement.average())
+        .fold(f64::INFINITY, f64::min);
+    for (name, measurement) in measurements {
+        info!(
+            "{} is {} times slower than the fastest",
+            name,
+            significant::precision(measurement.average() / fastest, PRECISION)
+        );
+    }
+}
+
+/// Executes single k256 public key recovery
+#[inline]
+fn k256_recover(_secp: &Secp256k1<VerifyOnly>, input: &SignedMessage) -> Result<[u8; 33], Error> {
+    use k256::ecdsa::{recoverable, Signature};
+
+    let signature = Signature::try_from(&input.signature[..]).map_err(invalid("k256 signature"))?;
+    let recovery_id =
+        recoverable::Id::new(input.recovery_id).map_err(invalid("k256 recovery id"))?;
+    let signature =
+        recoverable::Signature::new(&signature, recovery_id).map_err(invalid("k256 signature"))?;
+    let public_key = signature
+        .recover_verify_key_from_digest_bytes(&k256::FieldBytes::from(input.digest))
+        .map_err(failed("k256 recover"))?;
+
+    let mut result = [0u8; 33];
+    result.copy_from_slice(&public_key.to_bytes());
+    Ok(result)
+}
+
+/// Executes single libsecp256k1 public key recovery
+#[inline]
+fn libsecp256k1_recover(
+    _secp: &Secp256k1<VerifyOnly>,
+    input: &SignedMessage,
+) -> Result<[u8; 33], Error> {
+    use libsecp256k1::{Message, RecoveryId, Signature};
+
+    let message = Message::parse(&input.digest);
+    let signature = Signature::parse_standard_slice(&input.signature)
+        .map_err(invalid("libsecp256k1 signature"))?;
+    let recovery_id =
+        RecoveryId::parse(input.recovery_id).map_err(invalid("libsecp256k1 recovery id"))?;
+
+    Ok(libsecp256k1::recover(&message, &signature, &recovery_id)
+        .map_err(failed("libsecp256k1 recover"))?
+        .serialize_compressed())
+}
+
+/// Executes single secp256k1 public key recovery
+#[inline]
+fn secp256k1_recover(
+    secp: &Secp256k1<VerifyOnly>,
+    input: &SignedMessage,
+) -> Result<[u8; 33], Error> {
+    use secp256k1::recovery::{RecoverableSignature, RecoveryId};
+    use secp256k1::Message;
+
+    let message = Message::from_slice(&input.digest).map_err(invalid("secp256k1 message"))?;
+    let recovery_id =
+        RecoveryId::from_i32(input.recovery_id as i32).map_err(invalid("secp256k1 recovery id"))?;
+    let signature = RecoverableSignature::from_compact(&input.signature, recovery_id)
+        .map_err(invalid("secp256k1 signature"))?;
+
+    Ok(secp
+        .recover(&message, &signature)
+        .map_err(failed("secp256k1 recover"))?
+        .serialize())
+}
+
+/// Executes single k256 signature verification
+#[inline]
+fn k256_verify(_secp: &Secp256k1<VerifyOnly>, input: &SignedMessage) -> Result<bool, Error> {
+    use k256::ecdsa::Signature;
+
+    let public_key =
+        k256::PublicKey::from_sec1_bytes(&input.public_key).map_err(invalid("k256 public key"))?;
+    let signature = Signature::try_from(&input.signature[..]).map_err(invalid("k256 signature"))?;
+    let z = k256::Scalar::from_bytes_reduced(&k256::FieldBytes::from(input.digest));
+
+    Ok(public_key
+        .as_affine()
+        .verify_prehashed(&z, &signature)
+        .is_ok())
+}
+
+/// Executes single libsecp256k1 signature verification
+#[inline]
+fn libsecp256k1_verify(
+    _secp: &Secp256k1<VerifyOnly>,
+    input: &SignedMessage,
+) -> Result<bool, Error> {
+    use libsecp256k1::{Message, PublicKey, Signature};
+
+    let message = Message::parse(&input.digest);
+    let signature = Signature::parse_standard_slice(&input.signature)
+        .map_err(invalid("libsecp256k1 signature"))?;
+    let public_key = PublicKey::parse_compressed(&input.public_key)
+        .map_err(invalid("libsecp256k1 public key"))?;
+
+    Ok(libsecp256k1::verify(&message, &signature, &public_key))
+}
+
+/// Executes single secp256k1 signature verification
+#[inline]
+fn secp256k1_verify(secp: &Secp256k1<VerifyOnly>, input: &SignedMessage) -> Result<bool, Error> {
+    use secp256k1::{Message, PublicKey, Signature};
+
+    let message = Message::from_slice(&input.digest).map_err(invalid("secp256k1 message"))?;
+    let signature =
+        Signature::from_compact(&input.signature).map_err(invalid("secp256k1 signature"))?;
+    let public_key =
+        PublicKey::from_slice(&input.public_key).map_err(invalid("secp256k1 public key"))?;
+
+    Ok(secp.verify(&message, &signature, &public_key).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::Clock;
+    use crate::options::{CacheMode, ClockKind};
+
+    #[test]
+    fn rejected_signatures_are_recorded_as_failed_rounds() {
+        let harness = Harness {
+            count: 5,
+            time: None,
+            precision: None,
+            cache: CacheMode::Hot,
+            scratch_size: 0,
+            clock: Clock::new(ClockKind::Wall).unwrap(),
+            counters: false,
+            keep_going: true,
+        };
+        let secp = Secp256k1::verification_only();
+        let mut input = generate_signed_message().unwrap();
+        input.digest[0] ^= 1;
+        for (name, verify) in VERIFIES.iter() {
+            let measurement = secp256k1_measure(name, &harness, None, || {
+                verified(name, *verify, &secp, &input)
+            })
+            .unwrap();
+            assert_eq!(measurement.executed, 5, "{}", name);
+            assert_eq!(measurement.failures.len(), 1, "{}", name);
+            assert_eq!(measurement.failures[0].input, 0, "{}", name);
+            assert_eq!(measurement.failures[0].rounds, 5, "{}", name);
+            assert_eq!(
+                measurement.failures[0].error,
+                rejected(name).to_string(),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use super::harness::{Harness, Measurement};
+use super::pool::InputPool;
+use super::PRECISION;
+use crate::error::Error;
+use crate::significant;
+use solana_sdk::hash;
+use tracing::info;
+
+const BENCHMARK_NAME: &str = "sha256";
+
+/// Runs the sha256 benchmark and returns the measurement
+pub fn sha256_bench(pool: &InputPool, harness: &Harness) -> Result<Measurement, Error> {
+    info!("> Start {} benchmark...", BENCHMARK_NAME);
+
+    let measurement = harness.measure(|i| {
+        sha256_run(pool.get(i));
+        Ok(true)
+    })?;
+
+    let total = measurement.total;
+    let n = measurement.executed as f64;
+    let average = measurement.average();
+
+    info!("Finish {}", BENCHMARK_NAME);
+    info!(
+        "{} ({} executions) elapsed {} s.",
+        BENCHMARK_NAME,
+        n,
+        significant::precision(total, PRECISION)
+    );
+    info!(
+        "{} average: {} s. ({})",
+        BENCHMARK_NAME,
+        significant::precision(average, PRECISION),
+        harness
+    );
+
+    Ok(measurement)
+}
+
+/// Executes single sha256 call
+#[inline]
+fn sha256_run(msg: &[u8]) {
+    let _ = hash::hash(msg);
+}
@@ -0,0 +1,291 @@
+use super::affinity::pin;
+use super::alignment::Alignment;
+use super::alt_bn128_addition::alt_bn128_run_addition;
+use super::alt_bn128_multiplication::alt_bn128_run_multiplication;
+use super::alt_bn128_pairing::alt_bn128_run_pairing;
+use super::harness::{Harness, Measurement};
+use super::keccak::keccak_run;
+use super::mapping::PoolMapping;
+use super::pool::{InputPool, PoolLimits};
+use super::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing, keccak, PRECISION};
+use crate::alt_bn128::{
+    SyscallAltBn128Addition, SyscallAltBn128Multiplication, SyscallAltBn128Pairing, SyscallContext,
+};
+use crate::error::Error;
+use crate::significant;
+use solana_sdk::alt_bn128::prelude::{
+    ALT_BN128_ADDITION_OUTPUT_LEN, ALT_BN128_MULTIPLICATION_OUTPUT_LEN,
+    ALT_BN128_PAIRING_OUTPUT_LEN,
+};
+use std::any::Any;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// Operation measured concurrently by the worker threads
+#[derive(Clone, Copy, Debug)]
+pub enum Operation {
+    Keccak,
+    Addition,
+    Multiplication,
+    Pairing,
+}
+
+/// Single call of an operation on the input of the given round, read from the pool or through
+/// its mapping
+type Runner = Box<dyn FnMut(&InputPool, &PoolMapping, usize) -> Result<(), Error>>;
+
+/// Result of the operation measured on a number of threads
+pub struct Throughput {
+    pub threads: usize,
+    /// Wall time from the common start of the threads to the last one finishing in seconds
+    pub elapsed: f64,
+    /// Size of a single input in bytes
+    pub input_size: usize,
+    /// Measurement of every thread
+    pub measurements: Vec<Measurement>,
+}
+
+impl Operation {
+    /// Name of the benchmark of the operation
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Keccak => keccak::BENCHMARK_NAME,
+            Operation::Addition => alt_bn128_addition::BENCHMARK_NAME,
+            Operation::Multiplication => alt_bn128_multiplication::BENCHMARK_NAME,
+            Operation::Pairing => alt_bn128_pairing::BENCHMARK_NAME,
+        }
+    }
+
+    /// Generates the inputs of a worker, the alt_bn128 ones at the host offset of the alignment
+    fn inputs(
+        &self,
+        limits: &PoolLimits,
+        count: usize,
+        size: usize,
+        alignment: &Alignment,
+    ) -> Result<InputPool, Error> {
+        let inputs = match self {
+            Operation::Keccak => return Ok(super::generate_buffers(limits, count, size)),
+            Operation::Addition => super::generate_buffers_add(limits, count)?,
+            Operation::Multiplication => super::generate_buffers_mul(limits, count)?,
+            Operation::Pairing => super::generate_buffers_pair(limits, count)?,
+        };
+        Ok(inputs.misalign(alignment.host_offset))
+    }
+
+    /// Size of the output of the operation in bytes
+    fn output_len(&self) -> usize {
+        match self {
+            Operation::Keccak => 0,
+            Operation::Addition => ALT_BN128_ADDITION_OUTPUT_LEN,
+            Operation::Multiplication => ALT_BN128_MULTIPLICATION_OUTPUT_LEN,
+            Operation::Pairing => ALT_BN128_PAIRING_OUTPUT_LEN,
+        }
+    }
+
+    /// Single call of the operation in the context with its own syscall
+    fn runner(&self, context: &SyscallContext) -> Runner {
+        match self {
+            Operation::Keccak => Box::new(|inputs, _, round| {
+                keccak_run(inputs.get(round));
+                Ok(())
+            }),
+            Operation::Addition => {
+                let syscall = SyscallAltBn128Addition::new(context);
+                Box::new(move |inputs, mapping, round| {
+                    alt_bn128_run_addition(
+                        &syscall,
+                        mapping.memory_mapping(),
+                        mapping.input_addr(inputs.index(round)),
+                        inputs.input_size() as u64,
+                    )
+                })
+            }
+            Operation::Multiplication => {
+                let syscall = SyscallAltBn128Multiplication::new(context);
+                Box::new(move |inputs, mapping, round| {
+                    alt_bn128_run_multiplication(
+                        &syscall,
+                        mapping.memory_mapping(),
+                        mapping.input_addr(inputs.index(round)),
+                        inputs.input_size() as u64,
+                    )
+                })
+            }
+            Operation::Pairing => {
+                let syscall = SyscallAltBn128Pairing::new(context);
+                Box::new(move |inputs, mapping, round| {
+                    alt_bn128_run_pairing(
+                        &syscall,
+                        mapping.memory_mapping(),
+                        mapping.input_addr(inputs.index(round)),
+                        inputs.input_size() as u64,
+                    )
+                })
+            }
+        }
+    }
+}
+
+impl Throughput {
+    /// Operations per second of all the threads together over the wall time, whatever clock
+    /// measures the latency
+    pub fn ops_per_sec(&self) -> f64 {
+        self.measurements
+            .iter()
+            .map(|measurement| measurement.executed)
+            .sum::<usize>() as f64
+            / self.elapsed
+    }
+
+    /// Average elapsed time of a call across the threads in seconds
+    pub fn latency(&self) -> f64 {
+        self.measurements
+            .iter()
+            .map(Measurement::average)
+            .sum::<f64>()
+            / self.measurements.len() as f64
+    }
+}
+
+/// Runs the operation concurrently on the given number of threads, each with its own inputs
+/// mapped once and pinned to its CPU if any. The measurements start together once every thread
+/// has generated and mapped its inputs, the wall time of the throughput runs from this start
+/// to the last thread finishing.
+#[allow(clippy::too_many_arguments)]
+pub fn throughput_bench(
+    operation: Operation,
+    threads: usize,
+    cpus: &[usize],
+    limits: &PoolLimits,
+    size: usize,
+    harness: &Harness,
+    context: &SyscallContext,
+    alignment: &Alignment,
+) -> Result<Throughput, Error> {
+    info!("> Start {} on {} threads...", operation.name(), threads);
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let workers: Vec<_> = (0..threads)
+        .map(|i| {
+            let barrier = barrier.clone();
+            let limits = *limits;
+            let harness = harness.clone();
+            let cpu = cpus.get(i).cloned();
+            let context = *context;
+            let alignment = *alignment;
+            thread::spawn(move || {
+                if let Some(cpu) = cpu {
+                    if let Err(e) = pin(cpu) {
+                        warn!("{}, thread {} runs unpinned", e, i);
+                    }
+                }
+                let inputs = match operation.inputs(&limits, harness.count, size, &alignment) {
+                    Ok(inputs) => inputs,
+                    Err(e) => return released(&barrier, e),
+                };
+                let mapping =
+                    match PoolMapping::new(&inputs, &context, &alignment, operation.output_len()) {
+                        Ok(mapping) => mapping,
+                        Err(e) => return released(&barrier, e),
+                    };
+                let mut run = operation.runner(&context);
+                barrier.wait();
+                let measurement =
+                    harness.measure_recording(|i, failures| match run(&inputs, &mapping, i) {
+                        Ok(()) => Ok(true),
+                        Err(err) => failures.record(&harness, inputs.index(i), err),
+                    })?;
+                Ok((inputs.input_size(), measurement))
+            })
+        })
+        .collect();
+    barrier.wait();
+    let start = Instant::now();
+
+    let (sizes, measurements): (Vec<usize>, Vec<Measurement>) = workers
+        .into_iter()
+        .map(|worker| {
+            worker
+                .join()
+                .unwrap_or_else(|panic| Err(panic_message(panic)))
+        })
+        .collect::<Result<Vec<_>, Error>>()?
+        .into_iter()
+        .unzip();
+    let throughput = Throughput {
+        threads,
+        elapsed: start.elapsed().as_secs_f64(),
+        input_size: sizes[0],
+        measurements,
+    };
+    info!(
+        "{} on {} threads: {} ops/s., latency {} s. ({})",
+        operation.name(),
+        threads,
+        significant::precision(throughput.ops_per_sec(), PRECISION),
+        significant::precision(throughput.latency(), PRECISION),
+        harness
+    );
+    Ok(throughput)
+}
+
+/// Logs the scaling of the throughput on many threads relative to a single thread
+pub fn scaling_report(operation: Operation, single: &Throughput, multi: &Throughput) {
+    let speedup = multi.ops_per_sec() / single.ops_per_sec();
+    info!(
+        "{} scales {} times on {} threads, efficiency {}%, latency {} times of a single thread",
+        operation.name(),
+        significant::precision(speedup, PRECISION),
+        multi.threads,
+        significant::precision(speedup / multi.threads as f64 * 100., PRECISION),
+        significant::precision(multi.latency() / single.latency(), PRECISION)
+    );
+}
+
+/// Waits at the barrier every thread has to reach and returns the error setting up the thread
+fn released<T>(barrier: &Barrier, error: Error) -> Result<T, Error> {
+    barrier.wait();
+    Err(error)
+}
+
+/// Error of a panicked benchmark thread with its panic message if it has one
+fn panic_message(panic: Box<dyn Any + Send>) -> Error {
+    let message = match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "panicked".to_owned(),
+        },
+    };
+    Error::Thread(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn measurement(executed: usize, total: f64) -> Measurement {
+        Measurement {
+            executed,
+            total,
+            samples: vec![],
+            failures: vec![],
+            errors: BTreeMap::new(),
+            counters: None,
+        }
+    }
+
+    #[test]
+    fn ops_per_sec_counts_the_rounds_over_the_wall_time() {
+        let throughput = Throughput {
+            threads: 2,
+            elapsed: 2.,
+            input_size: 0,
+            measurements: vec![measurement(10, 0.1), measurement(30, 0.1)],
+        };
+        assert_eq!(throughput.ops_per_sec(), 20.);
+    }
+}
@@ -1,5 +1,7 @@
 //! alt-bn128-bench command line interface definition
 
+use alt_bn128_bench::options::{Baseline, Bench, CacheMode, ClockKind, Format, Loader};
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -8,6 +10,19 @@ pub struct Application {
     #[structopt(short, long, help = "Number of rounds", default_value = "10000")]
     pub count: usize,
 
+    #[structopt(
+        long,
+        help = "Target duration of every benchmark in seconds, the number of rounds is estimated by a pilot run",
+        conflicts_with = "compute-budget"
+    )]
+    pub time: Option<f64>,
+
+    #[structopt(
+        long,
+        help = "Stop once the 95% confidence interval is narrower than this relative precision"
+    )]
+    pub precision: Option<f64>,
+
     #[structopt(
         short,
         long,
@@ -16,18 +31,268 @@ pub struct Application {
     )]
     pub size: usize,
 
+    #[structopt(
+        long,
+        help = "Maximum number of input buffers generated once and cycled through",
+        default_value = "1024"
+    )]
+    pub pool_size: usize,
+
+    #[structopt(
+        long,
+        help = "Maximum memory used by the input buffers in bytes",
+        default_value = "67108864"
+    )]
+    pub pool_memory: usize,
+
+    #[structopt(
+        long,
+        help = "Cache residency of the data: hot reuses a small working set, cold evicts caches between rounds",
+        default_value = "hot",
+        possible_values = &["hot", "cold"]
+    )]
+    pub cache: CacheMode,
+
+    #[structopt(
+        long,
+        help = "Size of the scratch buffer touched between rounds in cache-cold mode in bytes",
+        default_value = "67108864"
+    )]
+    pub scratch_size: usize,
+
+    #[structopt(
+        long,
+        help = "Clock measuring the elapsed time: process or thread CPU time, monotonic wall time or cycle counter",
+        default_value = "process",
+        possible_values = &["process", "thread", "wall", "tsc"]
+    )]
+    pub clock: ClockKind,
+
+    #[structopt(
+        long,
+        help = "Run every benchmark concurrently on this many threads and report the throughput scaling",
+        default_value = "1"
+    )]
+    pub threads: usize,
+
+    #[structopt(
+        long,
+        help = "Pin the benchmark thread to this CPU, the other threads take the following CPUs"
+    )]
+    pub pin_cpu: Option<usize>,
+
+    #[structopt(
+        long,
+        help = "Nice value of the benchmark threads, negative values need privileges",
+        allow_hyphen_values = true
+    )]
+    pub nice: Option<i32>,
+
+    #[structopt(
+        long,
+        help = "Run the benchmark threads with this FIFO real-time priority, needs privileges",
+        conflicts_with = "nice"
+    )]
+    pub realtime: Option<i32>,
+
+    #[structopt(
+        long,
+        help = "Collect cycles, instructions, branch misses and cache misses per round with Linux perf_event"
+    )]
+    pub counters: bool,
+
+    #[structopt(
+        long,
+        help = "Record the inputs failing a call and count the alt_bn128 errors in the report and continue instead of stopping the run"
+    )]
+    pub keep_going: bool,
+
+    #[structopt(
+        long,
+        help = "Compute budget charged by the alt_bn128 syscalls, no metering if not set"
+    )]
+    pub compute_budget: Option<u64>,
+
+    #[structopt(long, help = "Compute units per Addition call", default_value = "334")]
+    pub addition_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units per Multiplication call",
+        default_value = "3840"
+    )]
+    pub multiplication_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units for the first pair of a Pairing call",
+        default_value = "36364"
+    )]
+    pub pairing_first_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units for every other pair of a Pairing call",
+        default_value = "12121"
+    )]
+    pub pairing_other_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units per G1 Compression call",
+        default_value = "30"
+    )]
+    pub g1_compress_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units per G1 Decompression call",
+        default_value = "398"
+    )]
+    pub g1_decompress_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units per G2 Compression call",
+        default_value = "86"
+    )]
+    pub g2_compress_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Compute units per G2 Decompression call",
+        default_value = "13610"
+    )]
+    pub g2_decompress_cost: u64,
+
+    #[structopt(
+        long,
+        help = "Bytes the VM address of the alt_bn128 inputs is moved past an aligned address",
+        default_value = "0"
+    )]
+    pub misalign_vm: u64,
+
+    #[structopt(
+        long,
+        help = "Bytes the host address of the alt_bn128 inputs is moved past an aligned address",
+        default_value = "0"
+    )]
+    pub misalign_host: usize,
+
+    #[structopt(
+        long,
+        help = "Check the VM addresses for alignment too, as clusters without the enforce_aligned_host_addrs feature do"
+    )]
+    pub no_enforce_aligned_host_addrs: bool,
+
+    #[structopt(
+        long,
+        help = "Loader of the program calling the alt_bn128 syscalls, the deprecated one skips the alignment checks",
+        default_value = "upgradeable",
+        possible_values = &["deprecated", "bpf", "upgradeable"]
+    )]
+    pub loader: Loader,
+
+    #[structopt(
+        long,
+        help = "Hash the ratios are relative to",
+        default_value = "keccak",
+        possible_values = &["keccak", "sha256", "blake3"]
+    )]
+    pub baseline: Baseline,
+
+    #[structopt(long, help = "Do not measure the baseline and report no ratios")]
+    pub no_baseline: bool,
+
+    #[structopt(
+        long,
+        help = "Take the baseline average from a previous JSON report of this machine instead of measuring it",
+        conflicts_with = "no-baseline",
+        parse(from_os_str)
+    )]
+    pub baseline_report: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help = "Run all the baselines and check that the derived compute units agree"
+    )]
+    pub consistency: bool,
+
+    #[structopt(
+        long,
+        help = "Relative spread of compute units across baselines flagged by the consistency check",
+        default_value = "0.25"
+    )]
+    pub consistency_tolerance: f64,
+
+    #[structopt(
+        long,
+        help = "Write the results to a file instead of the standard output",
+        parse(from_os_str)
+    )]
+    pub output: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help = "Format of the results, json if written to a file, console otherwise",
+        possible_values = &["console", "json", "csv", "markdown"]
+    )]
+    pub format: Option<Format>,
+
+    #[structopt(
+        long,
+        help = "Compare the results with a previous JSON report and fail on regression",
+        parse(from_os_str)
+    )]
+    pub baseline_file: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help = "Append the results of the run to a JSONL history file",
+        parse(from_os_str)
+    )]
+    pub history: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help = "Relative slowdown of the average reported as a regression",
+        default_value = "0.05"
+    )]
+    pub regression_threshold: f64,
+
+    #[structopt(
+        long,
+        help = "Maximum p-value of the Mann-Whitney U test for a change to be significant",
+        default_value = "0.05"
+    )]
+    pub significance: f64,
+
     #[structopt(subcommand)]
-    pub bench: Option<Bench>,
+    pub command: Option<Command>,
 }
 
-#[derive(StructOpt, Eq, PartialEq)]
-pub enum Bench {
-    #[structopt(name = "add")]
-    Addition,
-    #[structopt(name = "mul")]
-    Multiplication,
-    #[structopt(name = "pair")]
-    Pairing,
+#[derive(StructOpt)]
+pub enum Command {
+    #[structopt(flatten)]
+    Bench(Bench),
+    #[structopt(
+        name = "compare",
+        about = "Compare two JSON reports and fail on regression"
+    )]
+    Compare {
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+    },
+    #[structopt(
+        name = "history",
+        about = "Print the trends of a JSONL history file and flag change points"
+    )]
+    History {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
 }
 
 /// Constructs an instance of the Application.
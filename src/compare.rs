@@ -0,0 +1,99 @@
+//! alt-bn128-bench compare module
+
+use crate::benchmark::PRECISION;
+use crate::report::Report;
+use crate::{significant, statistics};
+use tracing::{info, warn};
+
+/// Limits a slowdown has to exceed to be reported as a regression
+pub struct Thresholds {
+    /// Relative increase of the average elapsed time
+    pub regression: f64,
+    /// Maximum p-value of the Mann-Whitney U test
+    pub significance: f64,
+}
+
+/// Compares the benchmarks present in both reports, matched by name and parameters,
+/// returns `true` if any of them regressed beyond the thresholds
+pub fn compare(old: &Report, new: &Report, thresholds: &Thresholds) -> bool {
+    info!("> Comparison of {} benchmarks", new.results.len());
+    let known = !old.host.name.is_empty() && !new.host.name.is_empty();
+    if known && (old.host.name != new.host.name || old.host.cpu_model != new.host.cpu_model) {
+        warn!(
+            "Reports come from different machines, {} ({}) and {} ({})",
+            old.host.name, old.host.cpu_model, new.host.name, new.host.cpu_model
+        );
+    }
+    let mut regressed = false;
+
+    for result in &new.results {
+        let previous = match old.find(&result.name, &result.parameters) {
+            Some(previous) => previous,
+            None => {
+                warn!(
+                    "{} ({}) has no previous result, skipped",
+                    result.name, result.parameters
+                );
+                continue;
+            }
+        };
+
+        if previous.parameters.resolution_differs(&result.parameters) {
+            warn!(
+                "{} ({}): clock resolution changed from {} s. to {} s., the averages may not be comparable",
+                result.name,
+                result.parameters,
+                significant::precision(previous.parameters.clock_resolution, PRECISION),
+                significant::precision(result.parameters.clock_resolution, PRECISION)
+            );
+        }
+
+        let change = (result.average - previous.average) / previous.average;
+        let p = statistics::mann_whitney_u(&previous.samples, &result.samples);
+        let regression = p <= thresholds.significance && change > thresholds.regression;
+        let verdict = if p > thresholds.significance {
+            "no significant change"
+        } else if regression {
+            "REGRESSION"
+        } else if change < 0. {
+            "improvement"
+        } else {
+            "within threshold"
+        };
+
+        let message = format!(
+            "{} ({}): {} s. -> {} s., {}{}% (p = {}), {}",
+            result.name,
+            result.parameters,
+            significant::precision(previous.average, PRECISION),
+            significant::precision(result.average, PRECISION),
+            if change >= 0. { "+" } else { "" },
+            significant::precision(change * 100., PRECISION),
+            significant::precision(p, PRECISION),
+            verdict
+        );
+        if regression {
+            regressed = true;
+            warn!("{}", message);
+        } else {
+            info!("{}", message);
+        }
+    }
+
+    for result in &old.results {
+        if new.find(&result.name, &result.parameters).is_none() {
+            warn!(
+                "{} ({}) is missing from the new results",
+                result.name, result.parameters
+            );
+        }
+    }
+
+    if regressed {
+        warn!(
+            "Regression beyond {}% detected",
+            significant::precision(thresholds.regression * 100., PRECISION)
+        );
+    }
+    regressed
+}
@@ -0,0 +1,34 @@
+//! alt-bn128-bench error module
+
+use crate::alt_bn128::{alt_bn128_error_name, BpfError};
+use solana_rbpf::error::EbpfError;
+use solana_sdk::alt_bn128::prelude::AltBn128Error;
+
+/// Errors of the benchmark runner
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Input and output regions can not be mapped into the VM memory
+    #[error("Memory mapping failed: {0}")]
+    Mapping(EbpfError<BpfError>),
+    /// Syscall failed before computing the result
+    #[error("Syscall failed: {0}")]
+    Syscall(EbpfError<BpfError>),
+    /// alt_bn128 syscall returned a non-zero error code, decoded to its name
+    #[error("alt_bn128 syscall returned {}: {0}", alt_bn128_error_name(.0))]
+    AltBn128(AltBn128Error),
+    /// Clock can not be read
+    #[error("Clock failed: {0}")]
+    Clock(String),
+    /// Hardware counters can not be started, stopped or read
+    #[error("Hardware counters failed: {0}")]
+    Counters(String),
+    /// Benchmark thread panicked
+    #[error("Benchmark thread failed: {0}")]
+    Thread(String),
+    /// Input can not be parsed
+    #[error("Invalid input: {0}")]
+    Input(String),
+    /// Syscall succeeded with an output other than the expected one
+    #[error("Unexpected output: {0}")]
+    Output(String),
+}
@@ -0,0 +1,173 @@
+//! alt-bn128-bench history module
+
+use crate::benchmark::{separator, PRECISION};
+use crate::compare::Thresholds;
+use crate::report::{BenchmarkResult, Report};
+use crate::{significant, statistics};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Single run appended to the history
+#[derive(Deserialize, Serialize)]
+pub struct Entry {
+    /// Start of the run in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Commit the benchmark was built from
+    pub commit: Option<String>,
+    /// Version of solana-program, the SDK the benchmark was built with
+    pub sdk_version: Option<String>,
+    /// Command line arguments of the run
+    pub arguments: Vec<String>,
+    /// Results of the run
+    #[serde(flatten)]
+    pub report: Report,
+}
+
+impl Entry {
+    /// Entry of the current run
+    pub fn new(report: Report) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            commit: option_env!("BENCH_GIT_COMMIT").map(str::to_owned),
+            sdk_version: option_env!("BENCH_SDK_VERSION").map(str::to_owned),
+            arguments: std::env::args().skip(1).collect(),
+            report,
+        }
+    }
+}
+
+/// Appends the entry as a line of the JSONL history file
+pub fn append(path: &Path, entry: &Entry) -> Result<(), String> {
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Serializing history entry failed: {}", e))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| format!("Writing history {} failed: {}", path.display(), e))
+}
+
+/// Reads all the entries of the JSONL history file
+pub fn load(path: &Path) -> Result<Vec<Entry>, String> {
+    let history = fs::read_to_string(path)
+        .map_err(|e| format!("Reading history {} failed: {}", path.display(), e))?;
+    history
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                format!(
+                    "Parsing history {} line {} failed: {}",
+                    path.display(),
+                    i + 1,
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+/// Logs the trend of every benchmark across the runs on the same host with the same parameters,
+/// and flags the runs that changed significantly compared to the previous one
+pub fn trends(entries: &[Entry], thresholds: &Thresholds) {
+    // Series of (entry, result) of every benchmark in order of first appearance
+    let mut series: Vec<Vec<(&Entry, &BenchmarkResult)>> = Vec::new();
+    for entry in entries {
+        for result in &entry.report.results {
+            let same = |(first, previous): &(&Entry, &BenchmarkResult)| {
+                first.report.host.name == entry.report.host.name
+                    && first.report.host.cpu_model == entry.report.host.cpu_model
+                    && previous.name == result.name
+                    && previous.parameters.matches(&result.parameters)
+            };
+            match series.iter_mut().find(|runs| same(&runs[0])) {
+                Some(runs) => runs.push((entry, result)),
+                None => series.push(vec![(entry, result)]),
+            }
+        }
+    }
+
+    info!("> History of {} runs", entries.len());
+    for runs in series {
+        let (entry, first) = runs[0];
+        separator();
+        info!(
+            "{} ({}) on {} ({})",
+            first.name, first.parameters, entry.report.host.name, entry.report.host.cpu_model
+        );
+
+        let mut previous: Option<&BenchmarkResult> = None;
+        for (entry, result) in runs {
+            let trend = (result.average - first.average) / first.average;
+            let message = format!(
+                "{} {} sdk {}: {} s., {}{}% since first run",
+                utc(entry.timestamp),
+                entry.commit.as_deref().unwrap_or("-"),
+                entry.sdk_version.as_deref().unwrap_or("-"),
+                significant::precision(result.average, PRECISION),
+                if trend >= 0. { "+" } else { "" },
+                significant::precision(trend * 100., PRECISION)
+            );
+
+            match previous {
+                Some(previous) if is_change_point(previous, result, thresholds) => {
+                    let change = (result.average - previous.average) / previous.average;
+                    warn!(
+                        "{}, change point {}{}% from previous run",
+                        message,
+                        if change >= 0. { "+" } else { "" },
+                        significant::precision(change * 100., PRECISION)
+                    );
+                }
+                _ => info!("{}", message),
+            }
+            previous = Some(result);
+        }
+    }
+}
+
+/// Checks whether the result differs from the previous one beyond the thresholds in either direction
+fn is_change_point(
+    previous: &BenchmarkResult,
+    result: &BenchmarkResult,
+    thresholds: &Thresholds,
+) -> bool {
+    let change = (result.average - previous.average) / previous.average;
+    change.abs() > thresholds.regression
+        && statistics::mann_whitney_u(&previous.samples, &result.samples) <= thresholds.significance
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time
+fn utc(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
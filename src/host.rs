@@ -0,0 +1,119 @@
+//! alt-bn128-bench host module
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+
+/// CPU flags speeding up the bignum arithmetic of the curves
+const BIGNUM_FLAGS: [&str; 3] = ["adx", "bmi2", "avx2"];
+
+/// Machine and build the results were measured with
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Host {
+    /// Host name
+    pub name: String,
+    /// CPU model name
+    pub cpu_model: String,
+    /// Number of logical CPUs
+    pub cores: usize,
+    /// CPU flags relevant to bignum arithmetic present on the machine
+    pub cpu_flags: Vec<String>,
+    /// Frequency governor of the first CPU, if the frequency is scaled
+    pub governor: Option<String>,
+    /// Whether turbo boost is enabled, if it is controlled by the kernel
+    pub turbo: Option<bool>,
+    /// Kernel release
+    pub kernel: String,
+    /// Version of the compiler the benchmark was built with
+    pub rustc: String,
+    /// Target triple of the build
+    pub target: String,
+    /// Build profile, debug or release
+    pub profile: String,
+    /// Cargo features enabled in the build
+    pub features: Vec<String>,
+}
+
+impl Host {
+    /// Gathers the description of this machine and build
+    pub fn detect() -> Self {
+        let cpuinfo = read("/proc/cpuinfo").unwrap_or_default();
+        let field = |name: &str| {
+            cpuinfo
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(key, _)| key.trim() == name)
+                .map(|(_, value)| value.trim().to_owned())
+        };
+        let flags = field("flags").unwrap_or_default();
+
+        Self {
+            name: read("/proc/sys/kernel/hostname").unwrap_or_else(|| "unknown".to_owned()),
+            cpu_model: field("model name").unwrap_or_else(|| "unknown".to_owned()),
+            cores: cpuinfo
+                .lines()
+                .filter(|line| line.starts_with("processor"))
+                .count(),
+            cpu_flags: BIGNUM_FLAGS
+                .iter()
+                .filter(|flag| flags.split_whitespace().any(|f| f == **flag))
+                .map(|flag| flag.to_string())
+                .collect(),
+            governor: read("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            turbo: turbo(),
+            kernel: read("/proc/sys/kernel/osrelease").unwrap_or_else(|| "unknown".to_owned()),
+            rustc: option_env!("BENCH_RUSTC_VERSION")
+                .unwrap_or("unknown")
+                .to_owned(),
+            target: env!("BENCH_TARGET").to_owned(),
+            profile: env!("BENCH_PROFILE").to_owned(),
+            features: env!("BENCH_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Host {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}, {} cores, flags [{}], governor {}, turbo {}, kernel {}, {}, {} {}",
+            self.name,
+            self.cpu_model,
+            self.cores,
+            self.cpu_flags.join(" "),
+            self.governor.as_deref().unwrap_or("-"),
+            match self.turbo {
+                Some(true) => "on",
+                Some(false) => "off",
+                None => "-",
+            },
+            self.kernel,
+            self.rustc,
+            self.target,
+            self.profile
+        )?;
+        if !self.features.is_empty() {
+            write!(f, ", features [{}]", self.features.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether turbo boost is enabled, checks the Intel and the generic cpufreq knobs
+fn turbo() -> Option<bool> {
+    if let Some(no_turbo) = read("/sys/devices/system/cpu/intel_pstate/no_turbo") {
+        return Some(no_turbo == "0");
+    }
+    read("/sys/devices/system/cpu/cpufreq/boost").map(|boost| boost == "1")
+}
+
+/// Reads the trimmed contents of a kernel file
+fn read(path: &str) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|contents| contents.trim().to_owned())
+}
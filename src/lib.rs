@@ -0,0 +1,17 @@
+//! alt-bn128-bench library: the alt_bn128 syscalls, input generators and benchmark runner
+//! shared by the command line tool and the integration tests of other repositories
+
+#![deny(missing_docs)]
+
+pub mod alt_bn128;
+pub mod alt_bn128_compression;
+pub mod benchmark;
+pub mod compare;
+pub mod error;
+pub mod history;
+pub mod host;
+pub mod options;
+pub mod report;
+pub mod reporter;
+pub mod significant;
+pub mod statistics;
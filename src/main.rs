@@ -3,10 +3,16 @@
 #![deny(warnings)]
 #![deny(missing_docs)]
 
-mod alt_bn128;
-mod benchmark;
 mod cli;
-mod significant;
+
+use alt_bn128_bench::options::Format;
+use alt_bn128_bench::report::Report;
+use alt_bn128_bench::{alt_bn128, benchmark, compare, history, reporter};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use tracing::error;
 
 fn main() {
     init_logger();
@@ -22,6 +28,136 @@ fn init_logger() {
 }
 
 /// Dispatches CLI commands
-fn execute(app: cli::Application) {
-    benchmark::run(app.count, app.size, app.bench);
+fn execute(mut app: cli::Application) {
+    let thresholds = compare::Thresholds {
+        regression: app.regression_threshold,
+        significance: app.significance,
+    };
+    let bench = match app.command.take() {
+        Some(cli::Command::Compare { old, new }) => {
+            let regressed = compare::compare(&load(&old), &load(&new), &thresholds);
+            if regressed {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(cli::Command::History { file }) => {
+            let entries = history::load(&file).unwrap_or_else(|e| fail(&e));
+            history::trends(&entries, &thresholds);
+            return;
+        }
+        Some(cli::Command::Bench(bench)) => Some(bench),
+        None => None,
+    };
+
+    let context = alt_bn128::SyscallContext {
+        loader_id: app.loader.id(),
+        enforce_aligned_host_addrs: !app.no_enforce_aligned_host_addrs,
+        costs: alt_bn128::AltBn128Costs {
+            addition: app.addition_cost,
+            multiplication: app.multiplication_cost,
+            pairing_one_pair_cost_first: app.pairing_first_cost,
+            pairing_one_pair_cost_other: app.pairing_other_cost,
+            g1_compress: app.g1_compress_cost,
+            g1_decompress: app.g1_decompress_cost,
+            g2_compress: app.g2_compress_cost,
+            g2_decompress: app.g2_decompress_cost,
+        },
+        ..Default::default()
+    };
+    let baseline = if app.no_baseline {
+        benchmark::BaselineSource::Skip
+    } else if let Some(path) = &app.baseline_report {
+        benchmark::BaselineSource::Saved(app.baseline, Box::new(load(path)))
+    } else {
+        benchmark::BaselineSource::Measure(app.baseline)
+    };
+    let priority = match (app.nice, app.realtime) {
+        (_, Some(priority)) => Some(benchmark::Priority::RealTime(priority)),
+        (Some(nice), None) => Some(benchmark::Priority::Nice(nice)),
+        (None, None) => None,
+    };
+    let consistency = if app.consistency {
+        Some(benchmark::Consistency {
+            tolerance: app.consistency_tolerance,
+        })
+    } else {
+        None
+    };
+    let clock = benchmark::Clock::new(app.clock).unwrap_or_else(|e| fail(&e.to_string()));
+    let report = benchmark::run(benchmark::Settings {
+        size: app.size,
+        pool: benchmark::PoolLimits {
+            inputs: app.pool_size,
+            bytes: app.pool_memory,
+        },
+        harness: benchmark::Harness {
+            count: app.count,
+            time: app.time.map(Duration::from_secs_f64),
+            precision: app.precision,
+            cache: app.cache,
+            scratch_size: app.scratch_size,
+            clock,
+            counters: app.counters,
+            keep_going: app.keep_going,
+        },
+        bench,
+        baseline,
+        budget: app.compute_budget,
+        consistency,
+        threads: app.threads,
+        pin_cpu: app.pin_cpu,
+        priority,
+        context,
+        alignment: benchmark::Alignment {
+            vm_offset: app.misalign_vm,
+            host_offset: app.misalign_host,
+        },
+    })
+    .unwrap_or_else(|e| fail(&e.to_string()));
+
+    let written = match app.output {
+        Some(path) => {
+            let format = app.format.unwrap_or(Format::Json);
+            File::create(&path)
+                .and_then(|mut file| reporter::reporter(format).write(&report, &mut file))
+                .map_err(|e| format!("Writing report {} failed: {}", path.display(), e))
+        }
+        None => {
+            let format = app.format.unwrap_or(Format::Console);
+            benchmark::separator();
+            reporter::reporter(format)
+                .write(&report, &mut io::stdout().lock())
+                .map_err(|e| format!("Writing results failed: {}", e))
+        }
+    };
+    if let Err(e) = written {
+        fail(&e);
+    }
+    let regressed = match app.baseline_file {
+        Some(path) => {
+            println!();
+            compare::compare(&load(&path), &report, &thresholds)
+        }
+        None => false,
+    };
+    if let Some(path) = app.history {
+        if let Err(e) = history::append(&path, &history::Entry::new(report)) {
+            fail(&e);
+        }
+    }
+    if regressed {
+        std::process::exit(1);
+    }
+}
+
+/// Reads a report or exits
+fn load(path: &Path) -> Report {
+    Report::load(path).unwrap_or_else(|e| fail(&e))
+}
+
+/// Logs the error and exits
+fn fail(message: &str) -> ! {
+    error!("{}", message);
+    std::process::exit(2)
 }
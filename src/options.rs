@@ -0,0 +1,232 @@
+//! alt-bn128-bench options module
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable};
+use std::fmt;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+/// Benchmark run alone instead of all of them
+#[derive(StructOpt, Eq, PartialEq)]
+pub enum Bench {
+    /// alt_bn128 Addition
+    #[structopt(name = "add")]
+    Addition,
+    /// alt_bn128 Multiplication
+    #[structopt(name = "mul")]
+    Multiplication,
+    /// alt_bn128 Pairing
+    #[structopt(name = "pair")]
+    Pairing,
+    /// alt_bn128 G1 and G2 point Compression
+    #[structopt(name = "compress")]
+    Compression,
+    /// alt_bn128 G1 and G2 point Decompression
+    #[structopt(name = "decompress")]
+    Decompression,
+    /// G1 and G2 point validation, G2 Addition and Multiplication and the stages of the Pairing
+    #[structopt(name = "points")]
+    Points,
+    /// secp256k1 recover and verify of all the implementations
+    #[structopt(name = "secp")]
+    Secp256k1,
+    /// Baseline only
+    #[structopt(name = "keccak")]
+    Keccak,
+    /// Memory mapping creation and slice translation of an alt_bn128 call
+    #[structopt(name = "mapping")]
+    Mapping,
+    /// Alignment checks of the syscall memory translation at misaligned addresses
+    #[structopt(name = "alignment")]
+    Alignment,
+}
+
+/// Hash the ratios are relative to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Baseline {
+    /// keccak256 of the solana-sdk
+    Keccak,
+    /// sha256 of the solana-sdk
+    Sha256,
+    /// blake3
+    Blake3,
+}
+
+impl Baseline {
+    /// All the baselines
+    pub const ALL: [Baseline; 3] = [Baseline::Keccak, Baseline::Sha256, Baseline::Blake3];
+}
+
+impl FromStr for Baseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keccak" => Ok(Baseline::Keccak),
+            "sha256" => Ok(Baseline::Sha256),
+            "blake3" => Ok(Baseline::Blake3),
+            _ => Err(format!("Unknown baseline: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Baseline::Keccak => write!(f, "keccak"),
+            Baseline::Sha256 => write!(f, "sha256"),
+            Baseline::Blake3 => write!(f, "blake3"),
+        }
+    }
+}
+
+/// Cache residency of the data between the rounds
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CacheMode {
+    /// Small working set staying in the caches
+    Hot,
+    /// Caches evicted before every round
+    Cold,
+}
+
+impl FromStr for CacheMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hot" => Ok(CacheMode::Hot),
+            "cold" => Ok(CacheMode::Cold),
+            _ => Err(format!("Unknown cache mode: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for CacheMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheMode::Hot => write!(f, "hot"),
+            CacheMode::Cold => write!(f, "cold"),
+        }
+    }
+}
+
+/// Clock measuring the elapsed time
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClockKind {
+    /// CPU time of the process
+    Process,
+    /// CPU time of the calling thread
+    Thread,
+    /// Monotonic wall time
+    Wall,
+    /// Calibrated cycle counter, x86_64 only
+    Tsc,
+}
+
+impl FromStr for ClockKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "process" => Ok(ClockKind::Process),
+            "thread" => Ok(ClockKind::Thread),
+            "wall" => Ok(ClockKind::Wall),
+            "tsc" => Ok(ClockKind::Tsc),
+            _ => Err(format!("Unknown clock: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ClockKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClockKind::Process => write!(f, "process"),
+            ClockKind::Thread => write!(f, "thread"),
+            ClockKind::Wall => write!(f, "wall"),
+            ClockKind::Tsc => write!(f, "tsc"),
+        }
+    }
+}
+
+/// Format the results are written in
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// Aligned table for reading in a terminal
+    Console,
+    /// JSON report, read back by the comparison
+    Json,
+    /// Comma-separated values for spreadsheets
+    Csv,
+    /// Markdown table for pull requests and documentation
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "console" => Ok(Format::Console),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(format!("Unknown format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Console => write!(f, "console"),
+            Format::Json => write!(f, "json"),
+            Format::Csv => write!(f, "csv"),
+            Format::Markdown => write!(f, "markdown"),
+        }
+    }
+}
+
+/// Loader of the program calling the syscalls
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Loader {
+    /// Deprecated loader, skips the alignment checks of the memory translation
+    Deprecated,
+    /// Non-upgradeable loader
+    Bpf,
+    /// Upgradeable loader
+    Upgradeable,
+}
+
+impl Loader {
+    /// Program id of the loader
+    pub fn id(&self) -> Pubkey {
+        match self {
+            Loader::Deprecated => bpf_loader_deprecated::id(),
+            Loader::Bpf => bpf_loader::id(),
+            Loader::Upgradeable => bpf_loader_upgradeable::id(),
+        }
+    }
+}
+
+impl FromStr for Loader {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deprecated" => Ok(Loader::Deprecated),
+            "bpf" => Ok(Loader::Bpf),
+            "upgradeable" => Ok(Loader::Upgradeable),
+            _ => Err(format!("Unknown loader: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Loader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Loader::Deprecated => write!(f, "deprecated"),
+            Loader::Bpf => write!(f, "bpf"),
+            Loader::Upgradeable => write!(f, "upgradeable"),
+        }
+    }
+}
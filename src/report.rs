@@ -0,0 +1,248 @@
+//! alt-bn128-bench report module
+
+use crate::host::Host;
+use crate::statistics;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Ratio of the clock resolutions of two results beyond which their averages are not comparable
+const RESOLUTION_FACTOR: f64 = 2.;
+
+/// Results of a benchmark run, saved as JSON
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Report {
+    /// Machine and build the results were measured with, missing in older reports
+    #[serde(default)]
+    pub host: Host,
+    /// CPUs the benchmark threads were pinned to, empty if not pinned
+    #[serde(default)]
+    pub cpus: Vec<usize>,
+    /// Results of every benchmark in the order they ran
+    pub results: Vec<BenchmarkResult>,
+}
+
+/// Parameters a benchmark result is measured with
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Parameters {
+    /// Size of a single input in bytes
+    pub input_size: usize,
+    /// Cache residency of the data between the rounds
+    pub cache: String,
+    /// Clock measuring the elapsed time
+    pub clock: String,
+    /// Measured resolution of the clock in seconds, zero in reports saved before it was recorded.
+    /// It varies between runs and is not matched, see `Parameters::resolution_differs`
+    #[serde(default)]
+    pub clock_resolution: f64,
+    /// Number of threads running the benchmark concurrently
+    #[serde(default = "single_thread")]
+    pub threads: usize,
+}
+
+/// Result of a single benchmark
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BenchmarkResult {
+    /// Name of the benchmark
+    pub name: String,
+    /// Parameters the result is matched by
+    pub parameters: Parameters,
+    /// Number of executed rounds
+    pub rounds: usize,
+    /// Average elapsed time of a round in seconds
+    pub average: f64,
+    /// Sample standard deviation of the samples in seconds
+    #[serde(default)]
+    pub std_dev: f64,
+    /// Half-width of the 95% confidence interval of the samples relative to the average
+    #[serde(default)]
+    pub confidence: f64,
+    /// Baseline the ratio is relative to, none for the baselines themselves
+    #[serde(default)]
+    pub baseline: Option<String>,
+    /// Average relative to the baseline average, the K-ratio
+    #[serde(default)]
+    pub ratio: Option<f64>,
+    /// Average elapsed time of a round in every batch in seconds
+    pub samples: Vec<f64>,
+    /// Inputs failing a round, recorded when the run keeps going
+    #[serde(default)]
+    pub failures: Vec<Failure>,
+    /// Number of rounds failing with every alt_bn128 error, by the error name,
+    /// counted when the run keeps going
+    #[serde(default)]
+    pub errors: BTreeMap<String, usize>,
+    /// Hardware events per round, collected with `--counters`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters: Option<CounterAverages>,
+}
+
+/// Average hardware events of a round
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CounterAverages {
+    /// CPU cycles
+    pub cycles: f64,
+    /// Retired instructions
+    pub instructions: f64,
+    /// Mispredicted branches
+    pub branch_misses: f64,
+    /// Last level cache misses
+    pub cache_misses: f64,
+}
+
+/// Input a benchmark failed on
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Failure {
+    /// Index of the input in the pool
+    pub input: usize,
+    /// Number of failed rounds on the input
+    pub rounds: usize,
+    /// Error of the first failed round
+    pub error: String,
+}
+
+impl BenchmarkResult {
+    /// Creates the result with the statistics of the samples
+    pub fn new(
+        name: &str,
+        parameters: Parameters,
+        rounds: usize,
+        average: f64,
+        samples: Vec<f64>,
+    ) -> Self {
+        let (std_dev, confidence) = if samples.len() > 1 {
+            (
+                statistics::std_dev(&samples),
+                statistics::relative_confidence(&samples),
+            )
+        } else {
+            (0., 0.)
+        };
+        Self {
+            name: name.to_owned(),
+            parameters,
+            rounds,
+            average,
+            std_dev,
+            confidence,
+            baseline: None,
+            ratio: None,
+            samples,
+            failures: Vec::new(),
+            errors: BTreeMap::new(),
+            counters: None,
+        }
+    }
+
+    /// Sets the ratio relative to the given baseline name and average
+    pub fn relative_to(mut self, baseline: Option<(String, f64)>) -> Self {
+        if let Some((name, k)) = baseline {
+            self.ratio = Some(self.average / k);
+            self.baseline = Some(name);
+        }
+        self
+    }
+}
+
+impl Report {
+    /// Reads the report from a JSON file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Reading report {} failed: {}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Parsing report {} failed: {}", path.display(), e))
+    }
+
+    /// Finds the result of the benchmark with the given name and parameters
+    pub fn find(&self, name: &str, parameters: &Parameters) -> Option<&BenchmarkResult> {
+        self.results
+            .iter()
+            .find(|result| result.name == name && result.parameters.matches(parameters))
+    }
+}
+
+impl Parameters {
+    /// Whether the results were measured the same way: input size, cache mode, clock and threads
+    pub fn matches(&self, other: &Parameters) -> bool {
+        self.input_size == other.input_size
+            && self.cache == other.cache
+            && self.clock == other.clock
+            && self.threads == other.threads
+    }
+
+    /// Whether the clock resolutions of both results are known and one is more than
+    /// `RESOLUTION_FACTOR` times coarser than the other
+    pub fn resolution_differs(&self, other: &Parameters) -> bool {
+        let (fine, coarse) = if self.clock_resolution < other.clock_resolution {
+            (self.clock_resolution, other.clock_resolution)
+        } else {
+            (other.clock_resolution, self.clock_resolution)
+        };
+        fine > 0. && coarse > fine * RESOLUTION_FACTOR
+    }
+}
+
+impl fmt::Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "input {} bytes, cache {}, clock {}",
+            self.input_size, self.cache, self.clock
+        )?;
+        if self.threads > 1 {
+            write!(f, ", {} threads", self.threads)?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of threads of the results saved before the multi-threaded mode
+fn single_thread() -> usize {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result() -> BenchmarkResult {
+        let parameters = Parameters {
+            input_size: 128,
+            cache: "hot".to_owned(),
+            clock: "process".to_owned(),
+            clock_resolution: 1E-9,
+            threads: 1,
+        };
+        BenchmarkResult::new("alt_bn128 Addition", parameters, 10, 1E-5, vec![1E-5])
+    }
+
+    #[test]
+    fn counters_are_written_when_collected() {
+        let mut result = result();
+        result.counters = Some(CounterAverages {
+            cycles: 30000.,
+            instructions: 60000.,
+            branch_misses: 20.,
+            cache_misses: 5.,
+        });
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["counters"]["cycles"], 30000.);
+        assert_eq!(json["counters"]["instructions"], 60000.);
+        assert_eq!(json["counters"]["branch_misses"], 20.);
+        assert_eq!(json["counters"]["cache_misses"], 5.);
+
+        let read: BenchmarkResult = serde_json::from_value(json).unwrap();
+        assert_eq!(read.counters, result.counters);
+    }
+
+    #[test]
+    fn counters_are_left_out_when_not_collected() {
+        let json = serde_json::to_value(result()).unwrap();
+        assert!(json.get("counters").is_none());
+
+        let read: BenchmarkResult = serde_json::from_value(json).unwrap();
+        assert_eq!(read.counters, None);
+    }
+}
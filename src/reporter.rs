@@ -0,0 +1,180 @@
+//! alt-bn128-bench reporter module
+
+use crate::benchmark::PRECISION;
+use crate::options::Format;
+use crate::report::{BenchmarkResult, Report};
+use crate::significant;
+use std::io::{self, Write};
+
+/// Column titles of the tabular formats
+const COLUMNS: [&str; 12] = [
+    "benchmark",
+    "input bytes",
+    "cache",
+    "clock",
+    "threads",
+    "rounds",
+    "average s.",
+    "std dev s.",
+    "confidence %",
+    "baseline",
+    "ratio",
+    "errors",
+];
+
+/// Writes the results of a run in some format
+pub trait Reporter {
+    /// Writes the report to the output
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Aligned table for reading in a terminal
+pub struct Console;
+
+/// JSON report, read back by the comparison
+pub struct Json;
+
+/// Comma-separated values with the full precision
+pub struct Csv;
+
+/// Markdown table
+pub struct Markdown;
+
+/// Reporter writing the given format
+pub fn reporter(format: Format) -> Box<dyn Reporter> {
+    match format {
+        Format::Console => Box::new(Console),
+        Format::Json => Box::new(Json),
+        Format::Csv => Box::new(Csv),
+        Format::Markdown => Box::new(Markdown),
+    }
+}
+
+impl Reporter for Console {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let rows: Vec<Vec<String>> = report.results.iter().map(rounded).collect();
+        let widths: Vec<usize> = COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                rows.iter()
+                    .map(|row| row[i].len())
+                    .chain(Some(title.len()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let line = |out: &mut dyn Write, cells: &[String]| {
+            let cells: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, width))| match i {
+                    0 | 2 | 3 | 9 | 11 => format!("{:<1$}", cell, width),
+                    _ => format!("{:>1$}", cell, width),
+                })
+                .collect();
+            writeln!(out, "{}", cells.join("  ").trim_end())
+        };
+
+        writeln!(out, "{}", report.host)?;
+        line(out, &titles())?;
+        for row in &rows {
+            line(out, row)?;
+        }
+        Ok(())
+    }
+}
+
+impl Reporter for Json {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, report)?;
+        writeln!(out)
+    }
+}
+
+impl Reporter for Csv {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let line = |out: &mut dyn Write, cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(|cell| quoted(cell)).collect();
+            writeln!(out, "{}", cells.join(","))
+        };
+        line(out, &titles())?;
+        for result in &report.results {
+            line(out, &exact(result))?;
+        }
+        Ok(())
+    }
+}
+
+impl Reporter for Markdown {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let line = |out: &mut dyn Write, cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            writeln!(out, "| {} |", cells.join(" | "))
+        };
+        writeln!(out, "Host: {}", report.host)?;
+        writeln!(out)?;
+        line(out, &titles())?;
+        let alignment: Vec<String> = (0..COLUMNS.len())
+            .map(|i| match i {
+                0 | 2 | 3 | 9 | 11 => ":---".to_owned(),
+                _ => "---:".to_owned(),
+            })
+            .collect();
+        writeln!(out, "|{}|", alignment.join("|"))?;
+        for result in &report.results {
+            line(out, &rounded(result))?;
+        }
+        Ok(())
+    }
+}
+
+/// Column titles as the cells of a row
+fn titles() -> Vec<String> {
+    COLUMNS.iter().map(|title| title.to_string()).collect()
+}
+
+/// Cells of the result with the figures rounded to the reported precision
+fn rounded(result: &BenchmarkResult) -> Vec<String> {
+    cells(result, |value: f64| {
+        significant::precision(value, PRECISION)
+    })
+}
+
+/// Cells of the result with the figures in full precision
+fn exact(result: &BenchmarkResult) -> Vec<String> {
+    cells(result, |value: f64| value.to_string())
+}
+
+/// Cells of the result in the order of the columns
+fn cells(result: &BenchmarkResult, figure: impl Fn(f64) -> String) -> Vec<String> {
+    vec![
+        result.name.clone(),
+        result.parameters.input_size.to_string(),
+        result.parameters.cache.clone(),
+        result.parameters.clock.clone(),
+        result.parameters.threads.to_string(),
+        result.rounds.to_string(),
+        figure(result.average),
+        figure(result.std_dev),
+        figure(result.confidence * 100.),
+        result.baseline.clone().unwrap_or_default(),
+        result.ratio.map(figure).unwrap_or_default(),
+        result
+            .errors
+            .iter()
+            .map(|(error, rounds)| format!("{} {}", error, rounds))
+            .collect::<Vec<_>>()
+            .join(", "),
+    ]
+}
+
+/// CSV field, quoted if it contains a separator, a quote or a line break
+fn quoted(cell: &str) -> String {
+    if cell.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
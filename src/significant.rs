@@ -9,11 +9,7 @@ pub fn precision(float: f64, prec: usize) -> String {
     let prec = if a >= 1. {
         // Reduce by number of digits, minimum 0
         let n = (1. + a.log10().floor()) as usize;
-        if n <= prec {
-            prec - n
-        } else {
-            0
-        }
+        prec.saturating_sub(n)
         // If precision is less than 1 (but non-zero), then precision becomes greater than "standard"
     } else if a > 0. {
         // Increase number of digits
@@ -0,0 +1,77 @@
+//! alt-bn128-bench statistics module
+
+/// Z-score of the two-sided 95% confidence level
+const Z_95: f64 = 1.96;
+
+/// Arithmetic mean of the samples
+pub fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+/// Sample standard deviation
+pub fn std_dev(samples: &[f64]) -> f64 {
+    if samples.len() < 2 {
+        return 0.;
+    }
+    let mean = mean(samples);
+    let variance =
+        samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (samples.len() - 1) as f64;
+    variance.sqrt()
+}
+
+/// Half-width of the 95% confidence interval of the mean relative to the mean
+pub fn relative_confidence(samples: &[f64]) -> f64 {
+    let half_width = Z_95 * std_dev(samples) / (samples.len() as f64).sqrt();
+    half_width / mean(samples)
+}
+
+/// Two-sided p-value of the Mann-Whitney U test that the samples come from the same distribution,
+/// uses the normal approximation with tie and continuity corrections
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> f64 {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    if a.is_empty() || b.is_empty() {
+        return 1.;
+    }
+
+    let mut values: Vec<(f64, bool)> = a
+        .iter()
+        .map(|x| (*x, true))
+        .chain(b.iter().map(|x| (*x, false)))
+        .collect();
+    values.sort_by(|x, y| x.0.partial_cmp(&y.0).expect("Sample is not a number"));
+
+    // Sum of the ranks of `a` with ties getting their average rank
+    let mut rank_sum = 0.;
+    let mut ties = 0.;
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j < values.len() && values[j].0 == values[i].0 {
+            j += 1;
+        }
+        let rank = (i + j + 1) as f64 / 2.;
+        let t = (j - i) as f64;
+        ties += t * t * t - t;
+        rank_sum += rank * values[i..j].iter().filter(|(_, first)| *first).count() as f64;
+        i = j;
+    }
+
+    let n = n1 + n2;
+    let u = rank_sum - n1 * (n1 + 1.) / 2.;
+    let mu = n1 * n2 / 2.;
+    let sigma = (n1 * n2 / 12. * (n + 1. - ties / (n * (n - 1.)))).sqrt();
+    if sigma == 0. {
+        return 1.;
+    }
+    let z = ((u - mu).abs() - 0.5).max(0.) / sigma;
+    erfc(z / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function, Abramowitz and Stegun 7.1.26, absolute error below 1.5e-7
+fn erfc(x: f64) -> f64 {
+    let t = 1. / (1. + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    poly * (-x * x).exp()
+}